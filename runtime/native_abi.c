@@ -88,16 +88,8 @@ long long _tr_rt_str_live_count(void) {
 void _tr_rt_print_i64(long long v) { printf("%lld\n", v); }
 void _tr_rt_print_cstr(const char* s) { fputs(s ? s : "", stdout); fputc('\n', stdout); }
 void _tr_rt_print_bool(long long v) { fputs(v ? "true" : "false", stdout); fputc('\n', stdout); }
-/* MSVCRT %g pads exponents to 3 digits ("1.5e+010"); tauraro prints "1.5e+10". */
-static void _tr_gnorm(char* b) {
-    char* e = strchr(b, 'e');
-    if (!e) return;
-    if ((e[1] == '+' || e[1] == '-') && e[2] == '0' && e[3] && e[4] && !e[5]) {
-        e[2] = e[3]; e[3] = e[4]; e[4] = 0;
-    }
-}
-static void _tr_gfmt(double v, char* b, size_t n) { snprintf(b, n, "%g", v); _tr_gnorm(b); }
-void _tr_rt_print_f64(double v) { char b[32]; _tr_gfmt(v, b, sizeof b); printf("%s\n", b); }   /* matches C backend's "%g" */
+static void _tr_gfmt(double v, char* b, size_t n) { _tr_float_repr_into(v, 0, b, n); }
+void _tr_rt_print_f64(double v) { char b[32]; _tr_gfmt(v, b, sizeof b); printf("%s\n", b); }   /* matches the C backend's repr(float) */
 void _tr_rt_write_f64(double v) { char b[32]; _tr_gfmt(v, b, sizeof b); printf("%s", b); }
 char* _tr_rt_char_to_str(long long c) { char b[2]; b[0]=(char)c; b[1]=0; return _tr_rt_str_new(b); }
 void _tr_rt_write_char(long long c) { putchar((int)c); }
@@ -597,7 +589,7 @@ void _tr_rt_sdict_remove(void* h, const char* k) {
 }
 
 /* whole-list printing, matching the C backend's formats exactly:
- *   ints  -> [1, 2, 3]     strs -> ['a', 'bb']     floats -> [1.5, 2, 3.25]  (%g) */
+ *   ints  -> [1, 2, 3]     strs -> ['a', 'bb']     floats -> [1.5, 2.0, 3.25]  (repr) */
 void _tr_rt_write_list_i64(void* h) {
     _TrNList* l = (_TrNList*)h;
     fputc('[', stdout);
@@ -425,6 +425,23 @@ static inline void* _tr_checked_alloc(size_t sz) {
     return p;
 }
 
+#ifdef _TR_MAIN
+  #define _TR_GLOBAL
+#else
+  #define _TR_GLOBAL extern
+#endif
+
+/* Thread-local storage qualifier for per-thread exception stacks */
+#if defined(TAURARO_BARE) || defined(TAURARO_KERNEL)
+#  define _TR_THREAD_LOCAL
+#elif defined(_MSC_VER)
+#  define _TR_THREAD_LOCAL __declspec(thread)
+#elif defined(__GNUC__) || defined(__clang__)
+#  define _TR_THREAD_LOCAL __thread
+#else
+#  define _TR_THREAD_LOCAL _Thread_local
+#endif
+
 /* ── Class-instance ARC ──────────────────────────────────────────────────────
  * A heap class instance carries a refcount as its FIRST member (`size_t __rc`,
  * so it sits at offset 0). Instances are shared by retain/released by scope-exit
@@ -441,10 +458,15 @@ static inline void* _tr_obj_retain(void* p) {
     if (p) (*(size_t*)p)++;
     return p;
 }
+/* Weak references to plain instances (std.weakref): count of this thread's
+ * weakly-referenced objects, and the hook that detaches them when freed. */
+_TR_GLOBAL _TR_THREAD_LOCAL size_t _tr_weakref_live;
+void _tr_weakref_forget(void* p);
 /* `drop` releases the instance's owned fields (generated per class). NULL for a
  * class with no droppable fields — the struct is still freed. */
 static inline void _tr_obj_release(void* p, void (*drop)(void*)) {
     if (p && --(*(size_t*)p) == 0) {
+        if (_tr_weakref_live) _tr_weakref_forget(p);
         if (drop) drop(p);
         _TR_MEMCOUNT_DEC();
         TAURARO_FREE(p);
@@ -646,6 +668,58 @@ static inline void _tr_weak_drop(_TrWeakBox* w) {
     }
     _tr_free(w);
 }
+/* ── weakref(obj) — Weak[T] to a plain (non-Shared) class instance ──
+ * A plain instance has no control block, so the first weakref to it creates one
+ * on the side: a _TrSharedBox whose data is the instance and whose strong count
+ * stays 1 while the instance lives. The per-thread table maps instance -> box
+ * (plain instances never cross threads, see [T-7]); _tr_obj_release calls
+ * _tr_weakref_forget when an entry's instance is freed, which marks the box
+ * dead so every Weak[T] to it upgrades to None. */
+_TrWeakBox* _tr_weakref_new(void* obj);
+#ifdef _TR_MAIN
+typedef struct _TrWeakrefSlot { void* obj; _TrSharedBox* box; struct _TrWeakrefSlot* next; } _TrWeakrefSlot;
+static _TR_THREAD_LOCAL _TrWeakrefSlot** _tr_weakref_tab;
+static _TR_THREAD_LOCAL size_t _tr_weakref_cap;
+static inline size_t _tr_weakref_slot(void* obj, size_t cap) { return (size_t)(((uintptr_t)obj >> 4) % cap); }
+_TrWeakBox* _tr_weakref_new(void* obj) {
+    if (!obj) return _tr_weak_new(NULL);
+    if (_tr_weakref_live >= _tr_weakref_cap) {
+        size_t ncap = _tr_weakref_cap ? _tr_weakref_cap * 2 : 64;
+        _TrWeakrefSlot** nt = (_TrWeakrefSlot**)_tr_checked_alloc(ncap * sizeof(_TrWeakrefSlot*));
+        for (size_t i = 0; i < _tr_weakref_cap; i++) {
+            _TrWeakrefSlot* e = _tr_weakref_tab[i];
+            while (e) { _TrWeakrefSlot* nx = e->next; size_t j = _tr_weakref_slot(e->obj, ncap); e->next = nt[j]; nt[j] = e; e = nx; }
+        }
+        if (_tr_weakref_tab) _tr_free(_tr_weakref_tab);
+        _tr_weakref_tab = nt;
+        _tr_weakref_cap = ncap;
+    }
+    size_t i = _tr_weakref_slot(obj, _tr_weakref_cap);
+    for (_TrWeakrefSlot* e = _tr_weakref_tab[i]; e; e = e->next)
+        if (e->obj == obj) return _tr_weak_new(e->box);
+    _TrWeakrefSlot* e = (_TrWeakrefSlot*)_tr_checked_alloc(sizeof(_TrWeakrefSlot));
+    e->obj = obj;
+    e->box = _tr_shared_new(obj, NULL);
+    e->next = _tr_weakref_tab[i];
+    _tr_weakref_tab[i] = e;
+    _tr_weakref_live++;
+    return _tr_weak_new(e->box);
+}
+void _tr_weakref_forget(void* p) {
+    _TrWeakrefSlot** link = &_tr_weakref_tab[_tr_weakref_slot(p, _tr_weakref_cap)];
+    for (_TrWeakrefSlot* e = *link; e; link = &e->next, e = e->next) {
+        if (e->obj != p) continue;
+        *link = e->next;
+        _tr_weakref_live--;
+        e->box->data = NULL;
+        atomic_store(&e->box->refcount, 0);
+        if (atomic_load(&e->box->weakcount) == 0) _tr_free(e->box);
+        _tr_free(e);
+        if (_tr_weakref_live == 0) { _tr_free(_tr_weakref_tab); _tr_weakref_tab = NULL; _tr_weakref_cap = 0; }
+        return;
+    }
+}
+#endif
 
 static inline void* _tr_c_memcpy(void* dst, void* src, size_t n) { return memcpy(dst, src, n); }
 static inline void* _tr_c_memset(void* ptr, int val, size_t n) { return memset(ptr, val, n); }
@@ -661,6 +735,9 @@ static inline size_t _tr_c_fread(void* ptr, size_t size, size_t nmemb, void* fp)
 static inline size_t _tr_c_fwrite(const void* ptr, size_t size, size_t nmemb, void* fp) { return fwrite(ptr, size, nmemb, (FILE*)fp); }
 static inline int _tr_c_fseek(void* fp, long offset, int whence) { return fseek((FILE*)fp, offset, whence); }
 static inline long _tr_c_ftell(void* fp) { return ftell((FILE*)fp); }
+static inline int _tr_c_fflush(void* fp) { return fflush((FILE*)fp); }
+/* 1 = stdout, 2 = stderr (std.io.stream's stdout()/stderr()). */
+static inline void* _tr_c_stdstream(long long fd) { return (void*)(fd == 2 ? stderr : stdout); }
 static inline char* _tr_getenv(const char* name) { char* v = getenv(name); return v ? v : ""; }
 #else
 static inline char* _tr_getenv(const char* name) { (void)name; return (char*)""; }
@@ -755,6 +832,12 @@ struct Result {
  * (void*)(double) or (double)(void*) cast is a hard error in C. */
 static inline void* _tr_f64_to_ptr(double d) { union { double d; void* p; } u; u.p = 0; u.d = d; return u.p; }
 static inline double _tr_ptr_to_f64(void* p) { union { double d; void* p; } u; u.d = 0; u.p = p; return u.d; }
+/* A float Dict key / Set element lives in the int-keyed TrIDict/_TrISet as its
+   f64 bit pattern. -0.0 is folded into 0.0 first: the two compare equal, so
+   they must find the same entry. An int key converts to double before this,
+   so d[1] finds the entry stored under 1.0, as in Python. */
+static inline long long _tr_f64_key(double d) { union { double d; long long k; } u; u.d = d == 0.0 ? 0.0 : d; return u.k; }
+static inline double _tr_key_f64(long long k) { union { double d; long long k; } u; u.k = k; return u.d; }
 
 /* ── Option[T] methods ───────────────────────────────────────────────── */
 static inline bool Option_is_some(Option self) { return self.tag == Option_Some; }
@@ -2141,6 +2224,37 @@ static int64_t _tr_int_lcm(int64_t a, int64_t b) {
     int64_t g = _tr_int_gcd(a, b); return g ? (a / g * b) : 0LL;
 }
 
+/* int + - * ** — a result that does not fit in 64 bits is an OverflowError
+ * (trap), never a silent wrap. Only `int`/`i64` operands come through here:
+ * the fixed-width and unsigned types keep C's wrapping arithmetic, and
+ * int.wrapping_add/sub/mul ask for it explicitly. */
+static void _tr_int_overflow(long long a, const char* op, long long b) {
+    _TR_DIAG("OverflowError: integer overflow in %lld %s %lld (int is 64-bit)\n", a, op, b);
+    _TR_TRAP();
+}
+static inline int64_t _tr_int_add(int64_t a, int64_t b) {
+    int64_t r; if (__builtin_expect(__builtin_add_overflow(a, b, &r), 0)) _tr_int_overflow(a, "+", b); return r;
+}
+static inline int64_t _tr_int_sub(int64_t a, int64_t b) {
+    int64_t r; if (__builtin_expect(__builtin_sub_overflow(a, b, &r), 0)) _tr_int_overflow(a, "-", b); return r;
+}
+static inline int64_t _tr_int_mul(int64_t a, int64_t b) {
+    int64_t r; if (__builtin_expect(__builtin_mul_overflow(a, b, &r), 0)) _tr_int_overflow(a, "*", b); return r;
+}
+/* Exact base ** exp by squaring. A negative exponent truncates toward zero
+ * (1 for base 1, +-1 for base -1, else 0), as the float pow() cast did. */
+static int64_t _tr_int_pow(int64_t base, int64_t exp) {
+    if (exp < 0) return base == 1 ? 1 : (base == -1 ? ((exp & 1) ? -1 : 1) : 0);
+    int64_t r = 1, b = base, e = exp; _Bool ov = 0;
+    while (e > 0) {
+        if (e & 1) ov |= __builtin_mul_overflow(r, b, &r);
+        e >>= 1;
+        if (e > 0) ov |= __builtin_mul_overflow(b, b, &b);
+    }
+    if (__builtin_expect(ov, 0)) _tr_int_overflow(base, "**", exp);
+    return r;
+}
+
 /* List sort/aggregate helpers are defined later in this header,
  * after all List_T typedefs.  See the v0.0.5 section near the end. */
 
@@ -3236,6 +3350,165 @@ static inline int  _tr_file_rename(const char* old_p, const char* new_p) { (void
 static inline long long _tr_file_size(const char* path)                  { (void)path; return -1LL; }
 #endif
 
+/* ── print(..., file=obj) ──────── std-tier only ─────────────────────────
+ * The line is formatted into an in-memory stream exactly as it would be to
+ * stdout, then handed to obj.write() as one owned TrStr. */
+#ifndef TAURARO_BARE
+typedef struct { FILE* f; char* buf; size_t len; } _TrPStream;
+static inline _TrPStream* _tr_pstream_open(void) {
+    _TrPStream* p = (_TrPStream*)_tr_checked_alloc(sizeof(_TrPStream));
+    p->buf = NULL; p->len = 0;
+#ifdef _WIN32
+    p->f = tmpfile();
+#else
+    p->f = open_memstream(&p->buf, &p->len);
+#endif
+    if (!p->f) { fprintf(stderr, "OSError: cannot buffer print() output\n"); exit(1); }
+    return p;
+}
+static inline TrStr _tr_pstream_take(_TrPStream* p) {
+#ifdef _WIN32
+    long n = ftell(p->f);
+    char* out = (char*)_tr_checked_alloc((size_t)(n > 0 ? n : 0) + 1);
+    rewind(p->f);
+    size_t got = n > 0 ? fread(out, 1, (size_t)n, p->f) : 0;
+    out[got] = '\0';
+    fclose(p->f);
+#else
+    fclose(p->f);
+    char* out = p->buf ? p->buf : strdup("");
+#endif
+    free(p);
+    return _tr_str_wrap_impl(out);
+}
+#endif
+
+/* ── Temporary files (std.tempfile) ──────── std-tier only ──────────────
+ * Names are <dir>/<prefix><8 random chars><suffix>, drawn from CPython's
+ * alphabet. A file is created with O_EXCL (mode 0600 on POSIX) and a
+ * directory with mkdir (0700), retrying on a name collision, so the caller
+ * owns a path nobody else could have created. Every path result is OWNED heap
+ * ("" on failure). */
+#ifndef TAURARO_BARE
+#include <errno.h>
+#ifndef _WIN32
+#include <fcntl.h>
+#endif
+static inline char* _tr_temp_dir(void) {
+    const char* envs[] = { "TMPDIR", "TEMP", "TMP" };
+    for (int i = 0; i < 3; i++) {
+        const char* v = getenv(envs[i]);
+        if (v && *v && _tr_dir_exists(v)) return strdup(v);
+    }
+#ifdef _WIN32
+    char buf[MAX_PATH + 1];
+    DWORD n = GetTempPathA(sizeof(buf), buf);
+    if (n > 0 && n < sizeof(buf)) {
+        while (n > 1 && (buf[n - 1] == '\\' || buf[n - 1] == '/')) buf[--n] = '\0';
+        return strdup(buf);
+    }
+    return strdup(".");
+#else
+    return strdup("/tmp");
+#endif
+}
+static inline void _tr_temp_name(char* out, size_t n, const char* dir, const char* prefix, const char* suffix) {
+    static const char alpha[] = "abcdefghijklmnopqrstuvwxyz0123456789_";
+    static _TR_THREAD_LOCAL unsigned long long st = 0;
+    if (st == 0) st = (unsigned long long)_tr_time_ns() ^ ((unsigned long long)_tr_getpid() << 32) ^ (unsigned long long)(uintptr_t)&st;
+    char rnd[9];
+    for (int i = 0; i < 8; i++) {
+        st ^= st << 13; st ^= st >> 7; st ^= st << 17;   /* xorshift64 */
+        rnd[i] = alpha[st % (sizeof(alpha) - 1)];
+    }
+    rnd[8] = '\0';
+#ifdef _WIN32
+    const char* sep = "\\";
+#else
+    const char* sep = "/";
+#endif
+    snprintf(out, n, "%s%s%s%s%s", dir, sep, prefix, rnd, suffix);
+}
+static inline char* _tr_mkstemp(const char* dir, const char* prefix, const char* suffix) {
+    char* d = (dir && *dir) ? strdup(dir) : _tr_temp_dir();
+    char path[4096];
+    for (int tries = 0; tries < 10000; tries++) {
+        _tr_temp_name(path, sizeof(path), d, prefix ? prefix : "", suffix ? suffix : "");
+#ifdef _WIN32
+        FILE* f = fopen(path, "wbx");
+        if (f) { fclose(f); free(d); return strdup(path); }
+        if (errno != EEXIST) break;
+#else
+        int fd = open(path, O_RDWR | O_CREAT | O_EXCL, 0600);
+        if (fd >= 0) { close(fd); free(d); return strdup(path); }
+        if (errno != EEXIST) break;
+#endif
+    }
+    free(d);
+    return strdup("");
+}
+static inline char* _tr_mkdtemp(const char* dir, const char* prefix, const char* suffix) {
+    char* d = (dir && *dir) ? strdup(dir) : _tr_temp_dir();
+    char path[4096];
+    for (int tries = 0; tries < 10000; tries++) {
+        _tr_temp_name(path, sizeof(path), d, prefix ? prefix : "", suffix ? suffix : "");
+#ifdef _WIN32
+        if (CreateDirectoryA(path, NULL)) { free(d); return strdup(path); }
+        if (GetLastError() != ERROR_ALREADY_EXISTS) break;
+#else
+        if (mkdir(path, 0700) == 0) { free(d); return strdup(path); }
+        if (errno != EEXIST) break;
+#endif
+    }
+    free(d);
+    return strdup("");
+}
+/* Remove a file or a whole directory tree; 0 on success. Symbolic links are
+ * removed, never followed. */
+static inline int _tr_rmtree(const char* path) {
+    if (!path || !*path) return -1;
+#ifdef _WIN32
+    DWORD attr = GetFileAttributesA(path);
+    if (attr == INVALID_FILE_ATTRIBUTES) return -1;
+    if (!(attr & FILE_ATTRIBUTE_DIRECTORY)) return DeleteFileA(path) ? 0 : -1;
+    if (!(attr & FILE_ATTRIBUTE_REPARSE_POINT)) {
+        char pat[4096]; snprintf(pat, sizeof(pat), "%s\\*", path);
+        WIN32_FIND_DATAA ffd;
+        HANDLE h = FindFirstFileA(pat, &ffd);
+        if (h != INVALID_HANDLE_VALUE) {
+            do {
+                if (strcmp(ffd.cFileName, ".") == 0 || strcmp(ffd.cFileName, "..") == 0) continue;
+                char child[4096]; snprintf(child, sizeof(child), "%s\\%s", path, ffd.cFileName);
+                _tr_rmtree(child);
+            } while (FindNextFileA(h, &ffd));
+            FindClose(h);
+        }
+    }
+    return RemoveDirectoryA(path) ? 0 : -1;
+#else
+    struct stat st;
+    if (lstat(path, &st) != 0) return -1;
+    if (!S_ISDIR(st.st_mode)) return unlink(path) == 0 ? 0 : -1;
+    DIR* dp = opendir(path);
+    if (dp) {
+        struct dirent* e;
+        while ((e = readdir(dp)) != NULL) {
+            if (strcmp(e->d_name, ".") == 0 || strcmp(e->d_name, "..") == 0) continue;
+            char child[4096]; snprintf(child, sizeof(child), "%s/%s", path, e->d_name);
+            _tr_rmtree(child);
+        }
+        closedir(dp);
+    }
+    return rmdir(path) == 0 ? 0 : -1;
+#endif
+}
+#else
+static inline char* _tr_temp_dir(void) { return strdup(""); }
+static inline char* _tr_mkstemp(const char* dir, const char* prefix, const char* suffix) { (void)dir; (void)prefix; (void)suffix; return strdup(""); }
+static inline char* _tr_mkdtemp(const char* dir, const char* prefix, const char* suffix) { (void)dir; (void)prefix; (void)suffix; return strdup(""); }
+static inline int   _tr_rmtree(const char* path) { (void)path; return -1; }
+#endif
+
 /* _tr_c_memset defined above */
 
 static inline void _tr_bounds_check(long long i, size_t len) {
@@ -3245,29 +3518,93 @@ static inline void _tr_bounds_check(long long i, size_t len) {
     }
 }
 
-#ifdef _TR_MAIN
-  #define _TR_GLOBAL
-#else
-  #define _TR_GLOBAL extern
-#endif
-
-/* Thread-local storage qualifier for per-thread exception stacks */
-#if defined(TAURARO_BARE) || defined(TAURARO_KERNEL)
-#  define _TR_THREAD_LOCAL
-#elif defined(_MSC_VER)
-#  define _TR_THREAD_LOCAL __declspec(thread)
-#elif defined(__GNUC__) || defined(__clang__)
-#  define _TR_THREAD_LOCAL __thread
-#else
-#  define _TR_THREAD_LOCAL _Thread_local
-#endif
-
 /* argc/argv made available to std.sys.env at runtime. */
 _TR_GLOBAL int    _tr_argc;
 _TR_GLOBAL char** _tr_argv;
 
+/* The compiler's module search path, one directory per line, set by main() (or
+ * tauraro_init() in a --lib build) when the program imports std.importlib.util
+ * or std.sys.imports (NULL otherwise). */
+_TR_GLOBAL const char* _tr_import_path;
+/* The modules compiled into the program, "<name>\t<path>" per line, set by
+ * main() (or tauraro_init() in a --lib build) when the program imports
+ * std.sys.imports (NULL otherwise). */
+_TR_GLOBAL const char* _tr_import_modules;
+
 static inline long long _tr_get_argc(void)       { return (long long)_tr_argc; }
-static inline char*     _tr_get_arg(long long n) { return (_tr_argv && n >= 0 && (int)n < _tr_argc) ? _tr_argv[(int)n] : (char*)""; }
+/* Owned copy: `-> str` extern results are wrapped rc=1 and released, which
+ * must not free() the argv storage itself. */
+static inline char*     _tr_get_arg(long long n) { return _tr_str_dup_owned((_tr_argv && n >= 0 && (int)n < _tr_argc) ? _tr_argv[(int)n] : ""); }
+static inline char*     _tr_import_path_get(void) { return _tr_str_dup_owned(_tr_import_path ? _tr_import_path : ""); }
+static inline char*     _tr_import_modules_get(void) { return _tr_str_dup_owned(_tr_import_modules ? _tr_import_modules : ""); }
+
+#ifdef TAURARO_PROFILE
+/* ── --profile: per-function call counts and timing ──────────────────────
+ * Codegen gives every user function a static _TrProfStat and opens a
+ * _TrProfFrame at entry; the frame's cleanup attribute closes it on every
+ * return path. "total" is inclusive and counted once per outermost activation
+ * (recursion is not double-counted); "self" excludes time spent in
+ * instrumented callees. Slots link themselves into one global list on first
+ * call, and the table is printed to stderr at exit. A frame unwound by a
+ * panic/raise is not closed, so its time is dropped (its call still counts). */
+typedef struct _TrProfStat {
+    const char* name;
+    long long   calls, total_ns, self_ns;
+    int         active, registered;
+    struct _TrProfStat* next;
+} _TrProfStat;
+typedef struct { _TrProfStat* st; long long t0; int depth; } _TrProfFrame;
+#define _TR_PROF_MAX_DEPTH 1024
+_TR_GLOBAL _TrProfStat* _tr_prof_head;
+_TR_GLOBAL _TR_THREAD_LOCAL int       _tr_prof_depth;
+_TR_GLOBAL _TR_THREAD_LOCAL long long _tr_prof_child[_TR_PROF_MAX_DEPTH];  /* callee time per open frame */
+void _tr_prof_report(void);
+#ifdef _TR_MAIN
+static int _tr_prof_cmp(const void* a, const void* b) {
+    long long x = (*(_TrProfStat* const*)a)->total_ns, y = (*(_TrProfStat* const*)b)->total_ns;
+    return (x < y) - (x > y);
+}
+void _tr_prof_report(void) {
+    int n = 0;
+    for (_TrProfStat* s = _tr_prof_head; s; s = s->next) n++;
+    if (n == 0) return;
+    _TrProfStat** v = (_TrProfStat**)malloc(sizeof(_TrProfStat*) * (size_t)n);
+    if (!v) return;
+    int i = 0;
+    for (_TrProfStat* s = _tr_prof_head; s; s = s->next) v[i++] = s;
+    qsort(v, (size_t)n, sizeof(_TrProfStat*), _tr_prof_cmp);
+    fflush(stdout);   /* atexit runs before stdio teardown: keep program output first */
+    fprintf(stderr, "\n--- profile: %d function%s ---\n", n, n == 1 ? "" : "s");
+    fprintf(stderr, "%12s %12s %12s  %s\n", "calls", "total ms", "self ms", "function");
+    for (i = 0; i < n; i++)
+        fprintf(stderr, "%12lld %12.3f %12.3f  %s\n", v[i]->calls,
+                (double)v[i]->total_ns / 1e6, (double)v[i]->self_ns / 1e6, v[i]->name);
+    free(v);
+}
+#endif
+static inline void _tr_prof_enter(_TrProfFrame* f, _TrProfStat* st) {
+    if (!__atomic_exchange_n(&st->registered, 1, __ATOMIC_ACQ_REL)) {
+        st->next = __atomic_load_n(&_tr_prof_head, __ATOMIC_ACQUIRE);
+        while (!__atomic_compare_exchange_n(&_tr_prof_head, &st->next, st, 0, __ATOMIC_ACQ_REL, __ATOMIC_ACQUIRE)) {}
+        if (!st->next) atexit(_tr_prof_report);   /* first slot registered */
+    }
+    __atomic_add_fetch(&st->calls, 1, __ATOMIC_RELAXED);
+    __atomic_add_fetch(&st->active, 1, __ATOMIC_RELAXED);
+    f->st = st;
+    f->depth = _tr_prof_depth++;
+    if (f->depth < _TR_PROF_MAX_DEPTH) _tr_prof_child[f->depth] = 0;
+    f->t0 = _tr_time_ns();
+}
+static inline void _tr_prof_leave(_TrProfFrame* f) {
+    long long dt = _tr_time_ns() - f->t0;
+    long long child = f->depth < _TR_PROF_MAX_DEPTH ? _tr_prof_child[f->depth] : 0;
+    if (f->depth > 0 && f->depth <= _TR_PROF_MAX_DEPTH) _tr_prof_child[f->depth - 1] += dt;
+    _tr_prof_depth = f->depth;
+    __atomic_add_fetch(&f->st->self_ns, dt - child, __ATOMIC_RELAXED);
+    if (__atomic_sub_fetch(&f->st->active, 1, __ATOMIC_RELAXED) == 0)
+        __atomic_add_fetch(&f->st->total_ns, dt, __ATOMIC_RELAXED);
+}
+#endif /* TAURARO_PROFILE */
 
 /* ── TaskGroup: spawn threads + join all (dynamic, unlimited) ────────── */
 typedef struct { _TrThread* ths; int count; int cap; } _TrTaskGroup;
@@ -3324,7 +3661,63 @@ static void _tr_exc_push(jmp_buf* b, char** m) {
     }
 }
 static void _tr_exc_pop(void)  { if (_tr_exc_sp > 0) _tr_exc_sp--; }
-static void _tr_exc_raise(char* msg) {
+
+/* Exception chaining. Every raise records an _TrExc beside its message:
+ * `cause` is the exception named by `raise X from Y`, `context` the one being
+ * handled (an except body was running) when X was raised. `raise X from None`
+ * sets suppress_context. An except body holds the record it caught on the
+ * handling stack until the body is left, by any path, through the cleanup on
+ * the depth returned by _tr_exc_handle_begin. Records are refcounted: the
+ * handling stack and the cause/context links each own a reference. */
+typedef struct _TrExc { char* msg; struct _TrExc* cause; struct _TrExc* context; int suppress_context; int rc; } _TrExc;
+_TR_GLOBAL _TR_THREAD_LOCAL _TrExc*  _tr_exc_cur;                 /* raised, not yet caught */
+_TR_GLOBAL _TR_THREAD_LOCAL _TrExc*  _tr_exc_hstack[_TR_MAX_EXC];  /* being handled, innermost last */
+_TR_GLOBAL _TR_THREAD_LOCAL int      _tr_exc_hsp;
+
+static _TrExc* _tr_exc_rec(char* msg) {
+    _TrExc* r = (_TrExc*)TAURARO_ALLOC(sizeof(_TrExc));
+    r->msg = msg; r->cause = NULL; r->context = NULL; r->suppress_context = 0; r->rc = 1;
+    return r;
+}
+static void _tr_exc_release(_TrExc* r) {
+    if (r && --r->rc == 0) { _tr_exc_release(r->cause); _tr_exc_release(r->context); TAURARO_FREE(r); }
+}
+/* The handled exception whose message is `msg` (the except binding being
+ * re-raised as a cause keeps its own chain), or a fresh record. */
+static _TrExc* _tr_exc_find_or_new(char* msg) {
+    for (int i = _tr_exc_hsp - 1; i >= 0; i--) {
+        _TrExc* h = _tr_exc_hstack[i];
+        if (h && h->msg && msg && (h->msg == msg || strcmp(h->msg, msg) == 0)) { h->rc++; return h; }
+    }
+    return _tr_exc_rec(msg);
+}
+static void _tr_exc_handle_end(int* depth) {
+    while (_tr_exc_hsp > *depth) _tr_exc_release(_tr_exc_hstack[--_tr_exc_hsp]);
+}
+/* Entering an except body of a try entered at handling depth `depth`. Records
+ * left above it by a raise that escaped a nested except body are dropped. */
+static int _tr_exc_handle_begin(int depth) {
+    _tr_exc_handle_end(&depth);
+    if (_tr_exc_hsp < _TR_MAX_EXC) _tr_exc_hstack[_tr_exc_hsp++] = _tr_exc_cur;
+    else _tr_exc_release(_tr_exc_cur);
+    _tr_exc_cur = NULL;
+    return depth;
+}
+/* An unhandled exception's chain, oldest first, each followed by Python's separator line. */
+static void _tr_exc_render_chain(_TrExc* r) {
+    _TrExc* prev = r->cause ? r->cause : (r->suppress_context ? NULL : r->context);
+    if (!prev) return;
+    _tr_exc_render_chain(prev);
+    _TR_DIAG("%s\n\n%s\n\n", prev->msg ? prev->msg : "(null)", r->cause
+        ? "The above exception was the direct cause of the following exception:"
+        : "During handling of the above exception, another exception occurred:");
+}
+/* Raise record `r` as it is, without touching its context: how an exception
+ * leaving a try through its finally block continues on to the next handler. */
+static void _tr_exc_resume(_TrExc* r) {
+    _tr_exc_release(_tr_exc_cur);
+    _tr_exc_cur = r;
+    char* msg = r->msg;
     if (_tr_exc_sp > 0) {
         _tr_exc_sp--;
         *_tr_exc_msgs[_tr_exc_sp] = msg;
@@ -3335,9 +3728,145 @@ static void _tr_exc_raise(char* msg) {
         _tr_thread_panic_message = msg;
         longjmp(_tr_thread_panic_jmpbuf, 1);
     }
+#if !defined(TAURARO_BARE) && !defined(TAURARO_KERNEL)
+    fflush(stdout);
+#endif
+    _tr_exc_render_chain(r);
     _TR_DIAG("Unhandled exception: %s\n", msg ? msg : "(null)");
     _TR_TRAP();
 }
+static void _tr_exc_throw(_TrExc* r) {
+    if (_tr_exc_hsp > 0 && _tr_exc_hstack[_tr_exc_hsp - 1]) {
+        r->context = _tr_exc_hstack[_tr_exc_hsp - 1];
+        r->context->rc++;
+    }
+    _tr_exc_resume(r);
+}
+/* The raised, not yet caught exception, which the caller now owns. */
+static _TrExc* _tr_exc_take(void) { _TrExc* r = _tr_exc_cur; _tr_exc_cur = NULL; return r; }
+static void _tr_exc_raise(char* msg) { _tr_exc_throw(_tr_exc_rec(msg)); }
+/* raise msg from cause; a NULL cause is `from None`. */
+static void _tr_exc_raise_from(char* msg, char* cause) {
+    _TrExc* r = _tr_exc_rec(msg);
+    if (cause) r->cause = _tr_exc_find_or_new(cause);
+    r->suppress_context = 1;
+    _tr_exc_throw(r);
+}
+
+/* / // % with a zero divisor raise ZeroDivisionError (catchable, Python's
+ * messages) instead of SIGFPE or inf. Integer quotients still truncate toward
+ * zero and remainders take the dividend's sign, as in C; float // truncates
+ * the same way. INT64_MIN / -1 does not fit and is an OverflowError. */
+static inline int64_t _tr_int_div(int64_t a, int64_t b) {
+    if (__builtin_expect(b == 0, 0)) _tr_exc_raise((char*)"ZeroDivisionError: integer division or modulo by zero");
+    if (__builtin_expect(b == -1 && a == INT64_MIN, 0)) _tr_int_overflow(a, "/", b);
+    return a / b;
+}
+static inline int64_t _tr_int_mod(int64_t a, int64_t b) {
+    if (__builtin_expect(b == 0, 0)) _tr_exc_raise((char*)"ZeroDivisionError: integer modulo by zero");
+    return b == -1 ? 0 : a % b;
+}
+static inline double _tr_float_div(double a, double b) {
+    if (__builtin_expect(b == 0.0, 0)) _tr_exc_raise((char*)"ZeroDivisionError: float division by zero");
+    return a / b;
+}
+static inline double _tr_float_floordiv(double a, double b) {
+    if (__builtin_expect(b == 0.0, 0)) _tr_exc_raise((char*)"ZeroDivisionError: float floor division by zero");
+    return __builtin_trunc(a / b);
+}
+static inline double _tr_float_mod(double a, double b) {
+    if (__builtin_expect(b == 0.0, 0)) _tr_exc_raise((char*)"ZeroDivisionError: float modulo by zero");
+    return __builtin_fmod(a, b);
+}
+
+/* A `with` item whose class defines __exit__. The guard is a cleanup variable,
+ * so it runs when the block is left by falling off the end, return, break or
+ * continue (never by longjmp): it drops the block's handler if a jump skipped
+ * the pop, then calls __exit__ through the class's _tr_with_exit_ thunk. The
+ * raise path clears `exit` and calls __exit__ itself with the exception. */
+typedef struct { void* ctx; void (*exit)(void*); int sp; } _TrWith;
+static void _tr_with_end(_TrWith* g) {
+    if (_tr_exc_sp > g->sp) _tr_exc_sp = g->sp;
+    if (g->exit) g->exit(g->ctx);
+}
+/* The type part of an exception message, "ValueError" for "ValueError: bad",
+ * copied into buf; a message without ':' is all type. */
+static char* _tr_exc_type_name(const char* msg, char* buf, size_t n) {
+    size_t i = 0;
+    if (msg) while (msg[i] && msg[i] != ':' && i + 1 < n) { buf[i] = msg[i]; i++; }
+    buf[i] = 0;
+    return buf;
+}
+
+#ifdef TAURARO_BUDGET
+/* ── Execution budget: --max-steps / --timeout-ms, std.sys.limits ────────
+ * Codegen emits _TR_BUDGET_TICK() at every function entry and at the top of
+ * every loop body. A step is one tick; the wall clock is read only every
+ * _TR_BUDGET_CHUNK steps. Once a limit is hit the budget stays tripped: the
+ * tick raises a TimeoutError through the exception stack (so `finally` blocks
+ * run) and raises again at the next tick, until the limit is lifted. With no
+ * handler the program prints the error and exits with status 124. The state
+ * is per-thread; a thread spawned under a budget starts unlimited. */
+#define _TR_BUDGET_CHUNK 1024LL
+#define _TR_BUDGET_MAX_NEST 16
+typedef struct { int on, hit; long long count, next, max, deadline; const char* msg; } _TrBudget;
+_TR_GLOBAL _TR_THREAD_LOCAL _TrBudget _tr_budget;
+_TR_GLOBAL _TR_THREAD_LOCAL _TrBudget _tr_budget_saved[_TR_BUDGET_MAX_NEST];
+_TR_GLOBAL _TR_THREAD_LOCAL int       _tr_budget_depth;
+static void _tr_budget_trip(const char* msg) {
+    _tr_budget.hit  = 1;
+    _tr_budget.msg  = msg;
+    _tr_budget.next = _tr_budget.count + 1;   /* re-trip at the very next tick */
+    if (_tr_exc_sp > 0 || _tr_thread_has_panic_buf) _tr_exc_raise((char*)msg);
+    fflush(stdout);
+    _TR_DIAG("%s\n", msg);
+    exit(124);
+}
+static void _tr_budget_check(void) {
+    if (_tr_budget.hit) _tr_budget_trip(_tr_budget.msg);
+    if (_tr_budget.max > 0 && _tr_budget.count >= _tr_budget.max)
+        _tr_budget_trip("TimeoutError: step budget exhausted");
+    if (_tr_budget.deadline > 0 && _tr_monotonic_ms() >= _tr_budget.deadline)
+        _tr_budget_trip("TimeoutError: time limit exceeded");
+    _tr_budget.next = _tr_budget.count + _TR_BUDGET_CHUNK;
+    if (_tr_budget.max > 0 && _tr_budget.next > _tr_budget.max) _tr_budget.next = _tr_budget.max;
+}
+#define _TR_BUDGET_TICK() do { if (_tr_budget.on && ++_tr_budget.count >= _tr_budget.next) _tr_budget_check(); } while (0)
+/* Start a budget of max_steps ticks and/or timeout_ms milliseconds (<= 0 means
+ * no limit of that kind). Nested budgets never loosen an enclosing one. */
+static inline void _tr_budget_enter(long long max_steps, long long timeout_ms) {
+    _TrBudget b = _tr_budget;
+    if (_tr_budget_depth < _TR_BUDGET_MAX_NEST) _tr_budget_saved[_tr_budget_depth] = b;
+    _tr_budget_depth++;
+    _TrBudget n = { 0, 0, 0, 0, 0, 0, NULL };
+    if (max_steps > 0) n.max = max_steps;
+    if (timeout_ms > 0) n.deadline = _tr_monotonic_ms() + timeout_ms;
+    if (b.on && b.max > 0 && (n.max == 0 || b.max - b.count < n.max)) n.max = b.max - b.count > 0 ? b.max - b.count : 1;
+    if (b.on && b.deadline > 0 && (n.deadline == 0 || b.deadline < n.deadline)) n.deadline = b.deadline;
+    n.on   = n.max > 0 || n.deadline > 0;
+    n.next = n.max > 0 && n.max < _TR_BUDGET_CHUNK ? n.max : _TR_BUDGET_CHUNK;
+    _tr_budget = n;
+}
+/* End the innermost budget; its steps count against the enclosing one. */
+static inline void _tr_budget_leave(void) {
+    long long used = _tr_budget.count;
+    if (_tr_budget_depth > 0) _tr_budget_depth--;
+    if (_tr_budget_depth < _TR_BUDGET_MAX_NEST) _tr_budget = _tr_budget_saved[_tr_budget_depth];
+    else _tr_budget.on = 0;
+    _tr_budget.count += used;
+    if (!_tr_budget.on || _tr_budget.hit) return;
+    /* The inner run may have used up the enclosing budget: mark it now so the
+     * enclosing run sees it, and raise at the next tick. */
+    const char* msg = NULL;
+    if (_tr_budget.max > 0 && _tr_budget.count >= _tr_budget.max) msg = "TimeoutError: step budget exhausted";
+    else if (_tr_budget.deadline > 0 && _tr_monotonic_ms() >= _tr_budget.deadline) msg = "TimeoutError: time limit exceeded";
+    if (msg) { _tr_budget.hit = 1; _tr_budget.msg = msg; _tr_budget.next = _tr_budget.count + 1; }
+}
+static inline bool      _tr_budget_exhausted(void) { return _tr_budget.hit != 0; }
+static inline long long _tr_budget_steps(void)     { return _tr_budget.count; }
+#else
+#define _TR_BUDGET_TICK() ((void)0)
+#endif /* TAURARO_BUDGET */
 
 /* ── String helpers ─────────────────────────────────────────────────── */
 
@@ -3431,7 +3960,55 @@ static char* _tr_i64_grouped(long long v) {
     for (int i=n-1,c=0;i>=0;i--,c++) { if (c&&c%3==0) b[w++]=','; b[w++]=t[i]; }
     b[w]=0; return b;
 }
-static char* _tr_float_to_str(double n)    { char* b=(char*)TAURARO_ALLOC(32); snprintf(b,32,"%g",n);   return b; }
+/* repr(float): the shortest decimal that reads back as the same value, laid out
+ * like CPython — fixed notation for decimal exponents -4..15 ("0.0001",
+ * "1000000000000000.0"), scientific outside it ("1e-05", "1e+16"), and a whole
+ * number keeps its ".0". `single` round-trips through float, for f32 values.
+ * `b` needs room for 32 bytes. */
+static char* _tr_float_repr_into(double v, int single, char* b, size_t n) {
+    if (__builtin_isnan(v)) { snprintf(b, n, "nan"); return b; }
+    if (__builtin_isinf(v)) { snprintf(b, n, v < 0 ? "-inf" : "inf"); return b; }
+#ifdef TAURARO_BARE
+    (void)single; snprintf(b, n, "%g", v); return b;
+#else
+    char e[40], t[40], dg[20]; int p, nd = 0, i;
+    for (p = 1; p < 17; p++) {
+        snprintf(e, sizeof e, "%.*e", p - 1, v);
+        double r = strtod(e, NULL);
+        if (single ? (float)r == (float)v : r == v) break;
+    }
+    if (p == 17) snprintf(e, sizeof e, "%.16e", v);
+    /* e is [-]d[.ddd]e(+|-)xx: pull out the digit string and the decimal exponent. */
+    const char* s = e; char* o = t;
+    if (*s == '-') { *o++ = '-'; s++; }
+    for (; *s && *s != 'e'; s++) if (*s != '.') dg[nd++] = *s;
+    int x = atoi(s + 1);
+    while (nd > 1 && dg[nd - 1] == '0') nd--;
+    if (x < -4 || x >= 16) {
+        *o++ = dg[0];
+        if (nd > 1) { *o++ = '.'; for (i = 1; i < nd; i++) *o++ = dg[i]; }
+        o += sprintf(o, "e%c%02d", x < 0 ? '-' : '+', x < 0 ? -x : x);
+    } else if (x < 0) {
+        *o++ = '0'; *o++ = '.';
+        for (i = 0; i < -x - 1; i++) *o++ = '0';
+        for (i = 0; i < nd; i++) *o++ = dg[i];
+    } else {
+        for (i = 0; i <= x; i++) *o++ = i < nd ? dg[i] : '0';
+        *o++ = '.';
+        if (nd > x + 1) { for (i = x + 1; i < nd; i++) *o++ = dg[i]; }
+        else *o++ = '0';
+    }
+    *o = '\0';
+    snprintf(b, n, "%s", t);
+    return b;
+#endif
+}
+/* Non-allocating repr for print/f-string arguments: the buffer is a compound
+ * literal that lives until the end of the enclosing block. */
+#define _TR_FREPR(v)   _tr_float_repr_into((double)(v), 0, (char[40]){0}, 40)
+#define _TR_F32REPR(v) _tr_float_repr_into((double)(v), 1, (char[40]){0}, 40)
+static char* _tr_float_to_str(double n)    { char* b=(char*)TAURARO_ALLOC(32); return _tr_float_repr_into(n,0,b,32); }
+static char* _tr_f32_to_str(float n)       { char* b=(char*)TAURARO_ALLOC(32); return _tr_float_repr_into((double)n,1,b,32); }
 static char* _tr_float_to_c_lit(double n) {
     char* b=(char*)TAURARO_ALLOC(32);
     int len = snprintf(b,32,"%.17g",n);
@@ -3461,7 +4038,7 @@ static inline char* _tr__u16_s(unsigned short x)     { return _tr_int_to_str((lo
 static inline char* _tr__i8_s(signed char x)         { return _tr_int_to_str((long long)x); }
 static inline char* _tr__u8_s(unsigned char x)       { return _tr_int_to_str((long long)x); }
 static inline char* _tr__dbl_s(double x)             { return _tr_float_to_str(x); }
-static inline char* _tr__flt_s(float x)              { return _tr_float_to_str((double)x); }
+static inline char* _tr__flt_s(float x)              { return _tr_f32_to_str(x); }
 static inline char* _tr__bool_s(bool x)              { return x ? "true" : "false"; }
 static inline char* _tr__ptr_s(void* x)              { return (char*)x; }
 static inline char* _tr__trstr_s(TrStr x)            { return x.data; }
@@ -3484,6 +4061,159 @@ static long long _tr_str_to_int(const char* s) { return s ? strtoll(s,NULL,10) :
 static double    _tr_str_to_float(const char* s){ return s ? strtod(s,NULL) : 0.0; }
 static long long _tr_strlen(char* s)     { return s ? (long long)strlen(s) : 0LL; }
 
+/* ── int() / float() from str and float: Python's conversion rules ────────
+ * Surrounding whitespace is ignored and `_` may separate digits. int(s, base)
+ * takes bases 2..36, or 0 to read the base from a 0x/0o/0b prefix (which is
+ * also accepted when it matches an explicit base). float(s) takes decimal and
+ * exponent forms plus inf/infinity/nan in any case. Anything else raises the
+ * ValueError Python raises; a value past the 64-bit range is an OverflowError. */
+static void _tr_conv_fail(const char* fmt, long long base, const char* s) {
+    size_t n = strlen(s) + 96;
+    char* m = (char*)TAURARO_ALLOC(n);
+    if (base >= 0) snprintf(m, n, fmt, base, s);
+    else snprintf(m, n, fmt, s);
+    _tr_exc_raise(m);
+}
+static int _tr_conv_word(const char* p, size_t n, const char* w) {
+    if (strlen(w) != n) return 0;
+    for (size_t i = 0; i < n; i++) if (tolower((unsigned char)p[i]) != w[i]) return 0;
+    return 1;
+}
+static long long _tr_int_parse(const char* s, long long base) {
+    if (!s) s = "";
+    if (base != 0 && (base < 2 || base > 36))
+        _tr_exc_raise((char*)"ValueError: int() base must be >= 2 and <= 36, or 0");
+    const char* bad = "ValueError: invalid literal for int() with base %lld: '%s'";
+    const char* p = s;
+    const char* e = s + strlen(s);
+    while (p < e && isspace((unsigned char)*p)) p++;
+    while (e > p && isspace((unsigned char)e[-1])) e--;
+    int neg = 0;
+    if (p < e && (*p == '+' || *p == '-')) { neg = *p == '-'; p++; }
+    long long b = base;
+    int prefixed = 0;
+    if (e - p >= 2 && p[0] == '0') {
+        int c = tolower((unsigned char)p[1]);
+        long long pb = c == 'x' ? 16 : c == 'o' ? 8 : c == 'b' ? 2 : 0;
+        if (pb && (b == 0 || b == pb)) { b = pb; p += 2; prefixed = 1; }
+    }
+    if (prefixed && p < e && *p == '_') p++;
+    if (b == 0) {
+        b = 10;
+        /* Python: a base-0 literal with a leading zero must be all zeros. */
+        if (p < e && *p == '0') {
+            for (const char* q = p; q < e; q++)
+                if (*q != '0' && *q != '_') _tr_conv_fail(bad, base, s);
+        }
+    }
+    unsigned long long limit = neg ? 9223372036854775808ULL : 9223372036854775807ULL;
+    unsigned long long acc = 0;
+    int ndig = 0, prev_us = 0;
+    for (; p < e; p++) {
+        if (*p == '_') {
+            if (ndig == 0 || prev_us) _tr_conv_fail(bad, base, s);
+            prev_us = 1;
+            continue;
+        }
+        int c = (unsigned char)*p, d;
+        if (c >= '0' && c <= '9') d = c - '0';
+        else if (isalpha(c)) d = tolower(c) - 'a' + 10;
+        else d = 99;
+        if (d >= b) _tr_conv_fail(bad, base, s);
+        if (acc > (limit - (unsigned long long)d) / (unsigned long long)b) {
+            _tr_conv_fail("OverflowError: int() literal too large for a 64-bit int: '%s'", -1, s);
+        }
+        acc = acc * (unsigned long long)b + (unsigned long long)d;
+        ndig++;
+        prev_us = 0;
+    }
+    if (ndig == 0 || prev_us) _tr_conv_fail(bad, base, s);
+    if (neg) return acc == 9223372036854775808ULL ? (long long)(-9223372036854775807LL - 1) : -(long long)acc;
+    return (long long)acc;
+}
+static double _tr_float_parse(const char* s) {
+    if (!s) s = "";
+    const char* bad = "ValueError: could not convert string to float: '%s'";
+    const char* p = s;
+    const char* e = s + strlen(s);
+    while (p < e && isspace((unsigned char)*p)) p++;
+    while (e > p && isspace((unsigned char)e[-1])) e--;
+    size_t n = (size_t)(e - p);
+    char* buf = (char*)malloc(n + 1);
+    size_t k = 0;
+    const char* q = p;
+    int neg = 0;
+    if (q < e && (*q == '+' || *q == '-')) { neg = *q == '-'; buf[k++] = *q++; }
+    size_t rest = (size_t)(e - q);
+    if (_tr_conv_word(q, rest, "inf") || _tr_conv_word(q, rest, "infinity")) {
+        free(buf);
+        return neg ? -__builtin_inf() : __builtin_inf();
+    }
+    if (_tr_conv_word(q, rest, "nan")) { free(buf); return __builtin_nan(""); }
+    /* [digits][.digits](e|E)[sign]digits, with `_` only between two digits. */
+    int mant = 0, ok = 1, seen_dot = 0;
+    for (; q < e; q++) {
+        char c = *q;
+        if (c >= '0' && c <= '9') { buf[k++] = c; mant++; continue; }
+        if (c == '_' && q > p && isdigit((unsigned char)q[-1]) && q + 1 < e && isdigit((unsigned char)q[1])) continue;
+        if (c == '.' && !seen_dot) { seen_dot = 1; buf[k++] = c; continue; }
+        break;
+    }
+    if (mant == 0) ok = 0;
+    if (ok && q < e && (*q == 'e' || *q == 'E')) {
+        buf[k++] = *q++;
+        if (q < e && (*q == '+' || *q == '-')) buf[k++] = *q++;
+        int edig = 0;
+        for (; q < e; q++) {
+            char c = *q;
+            if (c >= '0' && c <= '9') { buf[k++] = c; edig++; continue; }
+            if (c == '_' && isdigit((unsigned char)q[-1]) && q + 1 < e && isdigit((unsigned char)q[1])) continue;
+            break;
+        }
+        if (edig == 0) ok = 0;
+    }
+    if (q != e) ok = 0;
+    buf[k] = '\0';
+    double r = ok ? strtod(buf, NULL) : 0.0;
+    free(buf);
+    if (!ok) _tr_conv_fail(bad, -1, s);
+    return r;
+}
+/* int(x) of a float: truncates toward zero, like Python. */
+static long long _tr_float_to_int(double x) {
+    if (__builtin_isnan(x)) _tr_exc_raise((char*)"ValueError: cannot convert float NaN to integer");
+    if (__builtin_isinf(x)) _tr_exc_raise((char*)"OverflowError: cannot convert float infinity to integer");
+    if (x >= 9223372036854775808.0 || x < -9223372036854775808.0) {
+        char* m = (char*)TAURARO_ALLOC(96);
+        snprintf(m, 96, "OverflowError: float %.17g is out of range for a 64-bit int", x);
+        _tr_exc_raise(m);
+    }
+    return (long long)x;
+}
+/* str(b"..."): Python's bytes repr, b'...' (b"..." when the bytes hold a
+ * single quote and no double quote), escaping \t \n \r \\ and non-printables. */
+static char* _tr_bytes_repr(const char* s) {
+    if (!s) s = "";
+    char q = (strchr(s, '\'') && !strchr(s, '"')) ? '"' : '\'';
+    size_t n = strlen(s);
+    char* out = (char*)malloc(n * 4 + 4);
+    size_t k = 0;
+    out[k++] = 'b';
+    out[k++] = q;
+    for (const unsigned char* p = (const unsigned char*)s; *p; p++) {
+        unsigned char c = *p;
+        if (c == '\\' || c == (unsigned char)q) { out[k++] = '\\'; out[k++] = (char)c; }
+        else if (c == '\t') { out[k++] = '\\'; out[k++] = 't'; }
+        else if (c == '\n') { out[k++] = '\\'; out[k++] = 'n'; }
+        else if (c == '\r') { out[k++] = '\\'; out[k++] = 'r'; }
+        else if (c < 0x20 || c >= 0x7f) { k += (size_t)sprintf(out + k, "\\x%02x", c); }
+        else out[k++] = (char)c;
+    }
+    out[k++] = q;
+    out[k] = '\0';
+    return out;
+}
+
 /* ── String equality ─────────────────────────────────────────────────── */
 static inline bool _tr_str_eq(const char* a, const char* b) {
     if (!a && !b) return true;
@@ -3676,6 +4406,21 @@ static inline int _tr_system(const char* cmd) { (void)cmd; return -1; }
 static inline int _tr_system(const char* cmd) { return system(cmd); }
 #endif
 
+/* Exit code of a program run through _tr_system(): its own code, or 128 plus
+ * the signal that killed it, as a shell reports it. Passing system()'s raw
+ * wait status to exit() would keep only its low byte, which is 0 for an
+ * abort() or `exit(1)`. Windows' system() already returns the code. */
+#if defined(TAURARO_BARE) || defined(_WIN32)
+static inline int _tr_exit_code(int status) { return status; }
+#else
+#include <sys/wait.h>
+static inline int _tr_exit_code(int status) {
+    if (WIFEXITED(status)) return WEXITSTATUS(status);
+    if (WIFSIGNALED(status)) return 128 + WTERMSIG(status);
+    return status != 0;
+}
+#endif
+
 /* ── Panic / error ───────────────────────────────────────────────────── */
 static inline void _tr_panic(const char* msg) {
     if (_tr_thread_has_panic_buf) {
@@ -3692,23 +4437,33 @@ static inline bool _tr_contains(const char* haystack, const char* needle) {
     return haystack && needle && strstr(haystack, needle) != NULL;
 }
 
-/* ── Range iteration helper ──────────────────────────────────────────── */
-/* Note: range() on for-loops is compiled to C for() loops directly.     */
-/* This stub satisfies any residual reference in generic code paths.     */
-static inline long long _tr_range(long long start, long long end, long long step) {
-    (void)start; (void)end; (void)step;
-    return 0LL;
-}
-
 /* ── Dict (hash map: str → void*) ───────────────────────────────────── */
-
-typedef struct _DictNode { char* key; void* value; struct _DictNode* next; } _DictNode;
-typedef struct { _DictNode** buckets; size_t cap; size_t len; } Dict;
-
-static size_t _dict_hash(const char* k, size_t cap) {
+/* Besides its bucket chain (`next`), every node sits on a doubly-linked
+   insertion-order list (head → tail via `onext`), so keys()/values()/items()
+   and `for k in d` visit entries in the order they were first inserted, as
+   in Python. Overwriting a key keeps its position; removing unlinks it. */
+
+typedef struct _DictNode { char* key; void* value; struct _DictNode* next; struct _DictNode* onext; struct _DictNode* oprev; } _DictNode;
+/* `mods` counts inserts, removals and clears. A `for` over the dict snapshots
+   it and raises RuntimeError when it changes, instead of walking freed nodes. */
+typedef struct { _DictNode** buckets; size_t cap; size_t len; _DictNode* head; _DictNode* tail; size_t mods; } Dict;
+
+/* djb2 over the key bytes. hash(s) in Tauraro is this value; a Dict key's
+   bucket is it modulo the capacity. */
+static inline long long _tr_str_hash(const char* k) {
     size_t h=5381; unsigned char c;
     while ((c=(unsigned char)*k++)) h=h*33+c;
-    return h%cap;
+    return (long long)h;
+}
+static size_t _dict_hash(const char* k, size_t cap) {
+    return (size_t)_tr_str_hash(k)%cap;
+}
+/* hash(float): an integral value hashes like the int (hash(2.0) == hash(2));
+   anything else hashes its bit pattern. */
+static inline long long _tr_float_hash(double x) {
+    if (x > -9.2e18 && x < 9.2e18 && x == (double)(long long)x) return (long long)x;
+    long long b; memcpy(&b, &x, sizeof b);
+    return b ^ (b >> 32);
 }
 static Dict* Dict_new(void) {
     Dict* d=(Dict*)malloc(sizeof(Dict)); _TR_MEMCOUNT_INC(); _TR_MEMCOUNT_DICT_INC();
@@ -3716,7 +4471,7 @@ static Dict* Dict_new(void) {
        array is created on first insert. Saves one alloc per dict that stays
        empty - e.g. an HttpRequest's headers/params maps when a handler reads no
        headers and the route binds no params. All accessors below guard cap==0. */
-    d->cap=0; d->len=0; d->buckets=NULL;
+    d->cap=0; d->len=0; d->buckets=NULL; d->head=NULL; d->tail=NULL; d->mods=0;
     return d;
 }
 static void Dict_set(Dict* d, char* key, void* val) {
@@ -3726,7 +4481,10 @@ static void Dict_set(Dict* d, char* key, void* val) {
     _DictNode* n=d->buckets[i];
     while (n) { if (strcmp(n->key,key)==0) { n->value=val; return; } n=n->next; }
     _DictNode* nd=(_DictNode*)malloc(sizeof(_DictNode)); _TR_MEMCOUNT_INC();
-    nd->key=strdup(key); _TR_MEMCOUNT_INC(); nd->value=val; nd->next=d->buckets[i]; d->buckets[i]=nd; d->len++;
+    nd->key=strdup(key); _TR_MEMCOUNT_INC(); nd->value=val; nd->next=d->buckets[i]; d->buckets[i]=nd; d->len++; d->mods++;
+    nd->onext=NULL; nd->oprev=d->tail;
+    if (d->tail) d->tail->onext=nd; else d->head=nd;
+    d->tail=nd;
 }
 static void*     Dict_get(Dict* d, char* key) {
     if (!d||!key||d->cap==0) return NULL;
@@ -3744,8 +4502,11 @@ static void      Dict_remove(Dict* d, char* key) {
     while (n) {
         if (strcmp(n->key,key)==0) {
             if (prev) prev->next=n->next; else d->buckets[i]=n->next;
+            if (n->oprev) n->oprev->onext=n->onext; else d->head=n->onext;
+            if (n->onext) n->onext->oprev=n->oprev; else d->tail=n->oprev;
             if (n->key) _tr_free(n->key); _tr_free(n);
             if (d->len>0) d->len--;
+            d->mods++;
             return;
         }
         prev=n; n=n->next;
@@ -3799,7 +4560,7 @@ static void      Dict_clear_entries(Dict* d) {
         while (n) { _DictNode* nx=n->next; if(n->key) _tr_free(n->key); _tr_free(n); n=nx; }
         d->buckets[i]=NULL;
     }
-    d->len=0;
+    d->len=0; d->head=NULL; d->tail=NULL; d->mods++;
 }
 
 typedef Dict TrMap;
@@ -3816,8 +4577,9 @@ static inline long long _tr_dict_len(TrMap* d) { return Dict_len(d); }
 /* Map.update / Map.clear / Set[T] defined after List_str below */
 
 /* ── Int-keyed Dict (Dict[int, V]) ────────────────────────────────────── */
-typedef struct _TrIDictNode { long long key; void* value; struct _TrIDictNode* next; } _TrIDictNode;
-typedef struct { _TrIDictNode** buckets; size_t cap; size_t len; } TrIDict;
+/* Same insertion-order list as Dict (head → tail via `onext`). */
+typedef struct _TrIDictNode { long long key; void* value; struct _TrIDictNode* next; struct _TrIDictNode* onext; struct _TrIDictNode* oprev; } _TrIDictNode;
+typedef struct { _TrIDictNode** buckets; size_t cap; size_t len; _TrIDictNode* head; _TrIDictNode* tail; size_t mods; } TrIDict;
 static inline TrIDict* _tr_idict_new(long long cap_hint) {
     size_t cap = (size_t)(cap_hint > 8 ? cap_hint : 8);
     TrIDict* d = (TrIDict*)calloc(1, sizeof(TrIDict));
@@ -3831,7 +4593,10 @@ static inline void _tr_idict_set_impl(TrIDict* d, long long k, void* v) {
     while (n) { if (n->key == k) { n->value = v; return; } n = n->next; }
     _TrIDictNode* nd = (_TrIDictNode*)malloc(sizeof(_TrIDictNode));
     nd->key = k; nd->value = v; nd->next = d->buckets[idx];
-    d->buckets[idx] = nd; d->len++;
+    d->buckets[idx] = nd; d->len++; d->mods++;
+    nd->onext = NULL; nd->oprev = d->tail;
+    if (d->tail) d->tail->onext = nd; else d->head = nd;
+    d->tail = nd;
 }
 #define _tr_idict_set(d, k, v) _tr_idict_set_impl((d), (k), (void*)(uintptr_t)(v))
 static inline void* _tr_idict_get(TrIDict* d, long long k) {
@@ -3849,14 +4614,22 @@ static inline void   _tr_idict_remove(TrIDict* d, long long k) {
     while (n) {
         if (n->key == k) {
             if (prev) prev->next = n->next; else d->buckets[idx] = n->next;
+            if (n->oprev) n->oprev->onext = n->onext; else d->head = n->onext;
+            if (n->onext) n->onext->oprev = n->oprev; else d->tail = n->oprev;
             free(n);
             if (d->len > 0) d->len--;
+            d->mods++;
             return;
         }
         prev = n; n = n->next;
     }
 }
 static inline long long _tr_idict_len(TrIDict* d) { return d ? (long long)d->len : 0LL; }
+/* Raised by a dict/set `for` loop whose collection gained or lost a key. */
+static void _tr_dict_iter_changed(int is_set) {
+    _tr_exc_raise(is_set ? (char*)"RuntimeError: Set changed size during iteration"
+                         : (char*)"RuntimeError: dictionary changed size during iteration");
+}
 
 /* ── Built-in Tuple (up to 8 elements, all stored as long long) ────────── */
 typedef struct { long long data[8]; } TrTuple;
@@ -4006,41 +4779,33 @@ static inline void List_char_free(List_char* l) { if(l){ _tr_free(l->data); _tr_
 static inline List_TrStr* _tr_dict_keys(TrMap* d) {
     List_TrStr* out = List_TrStr_new();
     if (!d) return out;
-    for (size_t i = 0; i < d->cap; i++) {
-        _DictNode* n = d->buckets[i];
-        /* strdup the key: the returned TrStr owns its own buffer (rc=1), so
-           freeing the list (List_TrStr_free -> _tr_str_release) doesn't free
-           the dict's own key storage (which would dangle d's keys -> a later
-           d.get() / d[key] would miss). */
-        while (n) { if (n->key && n->value) List_TrStr_append_owned(out, _tr_str_wrap(strdup(n->key))); n = n->next; }
-    }
+    /* strdup the key: the returned TrStr owns its own buffer (rc=1), so
+       freeing the list (List_TrStr_free -> _tr_str_release) doesn't free
+       the dict's own key storage (which would dangle d's keys -> a later
+       d.get() / d[key] would miss). */
+    for (_DictNode* n = d->head; n; n = n->onext)
+        if (n->key && n->value) List_TrStr_append_owned(out, _tr_str_wrap(strdup(n->key)));
     return out;
 }
 static inline List_ptr* _tr_dict_values(TrMap* d) {
     List_ptr* out = List_ptr_new();
     if (!d) return out;
-    for (size_t i = 0; i < d->cap; i++) {
-        _DictNode* n = d->buckets[i];
-        while (n) { if (n->key && n->value) List_ptr_append(out, n->value); n = n->next; }
-    }
+    for (_DictNode* n = d->head; n; n = n->onext)
+        if (n->key && n->value) List_ptr_append(out, n->value);
     return out;
 }
 static inline List_i64* _tr_idict_keys(TrIDict* d) {
     List_i64* out = List_i64_new();
     if (!d) return out;
-    for (size_t i = 0; i < d->cap; i++) {
-        _TrIDictNode* n = d->buckets[i];
-        while (n) { if (n->value) List_i64_append(out, n->key); n = n->next; }
-    }
+    for (_TrIDictNode* n = d->head; n; n = n->onext)
+        if (n->value) List_i64_append(out, n->key);
     return out;
 }
 static inline List_ptr* _tr_idict_values(TrIDict* d) {
     List_ptr* out = List_ptr_new();
     if (!d) return out;
-    for (size_t i = 0; i < d->cap; i++) {
-        _TrIDictNode* n = d->buckets[i];
-        while (n) { if (n->value) List_ptr_append(out, n->value); n = n->next; }
-    }
+    for (_TrIDictNode* n = d->head; n; n = n->onext)
+        if (n->value) List_ptr_append(out, n->value);
     return out;
 }
 /* values() for Dict[K,str]/Map[K,str]: unbox+retain each boxed TrStr value
@@ -4049,19 +4814,23 @@ static inline List_ptr* _tr_idict_values(TrIDict* d) {
 static inline List_TrStr* _tr_dict_values_strval(TrMap* d) {
     List_TrStr* out = List_TrStr_new();
     if (!d) return out;
-    for (size_t i = 0; i < d->cap; i++) {
-        _DictNode* n = d->buckets[i];
-        while (n) { if (n->key && n->value) List_TrStr_append(out, _tr_str_unbox(n->value)); n = n->next; }
-    }
+    for (_DictNode* n = d->head; n; n = n->onext)
+        if (n->key && n->value) List_TrStr_append(out, _tr_str_unbox(n->value));
     return out;
 }
 static inline List_TrStr* _tr_idict_values_strval(TrIDict* d) {
     List_TrStr* out = List_TrStr_new();
     if (!d) return out;
-    for (size_t i = 0; i < d->cap; i++) {
-        _TrIDictNode* n = d->buckets[i];
-        while (n) { if (n->value) List_TrStr_append(out, _tr_str_unbox(n->value)); n = n->next; }
-    }
+    for (_TrIDictNode* n = d->head; n; n = n->onext)
+        if (n->value) List_TrStr_append(out, _tr_str_unbox(n->value));
+    return out;
+}
+/* keys() of a float-keyed dict / set: the stored f64 bits back as doubles. */
+static inline List_f64* _tr_idict_keys_f64(TrIDict* d) {
+    List_f64* out = List_f64_new();
+    if (!d) return out;
+    for (_TrIDictNode* n = d->head; n; n = n->onext)
+        if (n->value) List_f64_append(out, _tr_key_f64(n->key));
     return out;
 }
 
@@ -4072,26 +4841,20 @@ typedef struct { long long key; void* val; } TrIKVPair;
 static inline List_ptr* _tr_dict_items(TrMap* d) {
     List_ptr* out = List_ptr_new();
     if (!d) return out;
-    for (size_t i = 0; i < d->cap; i++) {
-        _DictNode* n = d->buckets[i];
-        while (n) {
-            TrKVPair* p = (TrKVPair*)malloc(sizeof(TrKVPair));
-            p->key = n->key; p->val = n->value;
-            List_ptr_append(out, p); n = n->next;
-        }
+    for (_DictNode* n = d->head; n; n = n->onext) {
+        TrKVPair* p = (TrKVPair*)malloc(sizeof(TrKVPair));
+        p->key = n->key; p->val = n->value;
+        List_ptr_append(out, p);
     }
     return out;
 }
 static inline List_ptr* _tr_idict_items(TrIDict* d) {
     List_ptr* out = List_ptr_new();
     if (!d) return out;
-    for (size_t i = 0; i < d->cap; i++) {
-        _TrIDictNode* n = d->buckets[i];
-        while (n) {
-            TrIKVPair* p = (TrIKVPair*)malloc(sizeof(TrIKVPair));
-            p->key = n->key; p->val = n->value;
-            List_ptr_append(out, p); n = n->next;
-        }
+    for (_TrIDictNode* n = d->head; n; n = n->onext) {
+        TrIKVPair* p = (TrIKVPair*)malloc(sizeof(TrIKVPair));
+        p->key = n->key; p->val = n->value;
+        List_ptr_append(out, p);
     }
     return out;
 }
@@ -4128,7 +4891,13 @@ static inline void List_f64_swap(List_f64* l, long long a, long long b) { if(!l|
 static inline void List_f64_clear(List_f64* l) { if(l) l->len=0; }
 static inline bool List_f64_is_empty(List_f64* l) { return !l||l->len==0; }
 static inline void List_f64_extend(List_f64* l, List_f64* o) { if(!l||!o) return; for(size_t i=0;i<o->len;i++) List_f64_append(l,o->data[i]); }
-static inline bool List_f64_contains(List_f64* l, double v) { if(!l) return false; for(size_t i=0;i<l->len;i++) if(l->data[i]==v) return true; return false; }
+/* Membership follows Python's `x is e or x == e`: an unboxed double has no
+ * identity, so a bit-identical element stands in for "the same object". That
+ * keeps `nan in [nan]` true while NaN still never compares == to anything,
+ * and -0.0/0.0 stay equal through the ordinary IEEE compare. */
+static inline bool _tr_f64_same(double a, double b) { return a==b || memcmp(&a,&b,sizeof(double))==0; }
+static inline bool List_f64_contains(List_f64* l, double v) { if(!l) return false; for(size_t i=0;i<l->len;i++) if(_tr_f64_same(l->data[i],v)) return true; return false; }
+static inline long long List_f64_index_of(List_f64* l, double v) { if(!l) return -1LL; for(size_t i=0;i<l->len;i++) if(_tr_f64_same(l->data[i],v)) return (long long)i; return -1LL; }
 static inline double List_f64_get(List_f64* l, long long i) { if(l&&(size_t)i<l->len) return l->data[i]; return 0.0; }
 static inline void List_f64_set(List_f64* l, long long i, double v) { if(l&&(size_t)i<l->len) l->data[i]=v; }
 static inline void List_str_remove(List_str* l, long long i) { if(!l||(size_t)i>=l->len) return; for(size_t j=(size_t)i;j<l->len-1;j++) l->data[j]=l->data[j+1]; l->len--; }
@@ -4177,6 +4946,449 @@ static inline void List_i32_extend(List_i32* l, List_i32* o) { if(!l||!o) return
 static inline bool List_i32_contains(List_i32* l, int v) { if(!l) return false; for(size_t i=0;i<l->len;i++) if(l->data[i]==v) return true; return false; }
 static inline int List_i32_pop(List_i32* l) { if(!l||l->len==0) return 0; l->len--; return l->data[l->len]; }
 
+/* ── Slices: xs.slice(...), xs.set_slice(...), xs.del_slice(...) ─────────── */
+/* Python's xs[start:stop:step], xs[start:stop:step] = values and
+ * del xs[start:stop:step]. Bounds are normalised the way CPython's
+ * PySlice_AdjustIndices does it: negative indices count from the end, anything
+ * out of range clamps, and with a negative step a stop of -len(xs)-1 or less
+ * means "past the front". A step-1 slice may be
+ * replaced by any number of values (the list grows or shrinks in place); an
+ * extended slice needs exactly as many values as it selects, else ValueError.
+ * The values are snapshotted first, so xs.set_slice(0, len(xs), xs) is safe. */
+static inline long long _tr_slice_adjust(long long n, long long* start, long long* stop, long long step) {
+    if (step == 0) _tr_exc_raise((char*)"ValueError: slice step cannot be zero");
+    long long lo = step < 0 ? -1 : 0, hi = step < 0 ? n - 1 : n;
+    if (*start < 0) { *start += n; if (*start < lo) *start = lo; } else if (*start > hi) *start = hi;
+    if (*stop < 0) { *stop += n; if (*stop < lo) *stop = lo; } else if (*stop > hi) *stop = hi;
+    if (step > 0) return *start < *stop ? (*stop - *start - 1) / step + 1 : 0;
+    return *start > *stop ? (*start - *stop - 1) / (-step) + 1 : 0;
+}
+static inline void _tr_slice_size_error(long long got, long long want) {
+    char* m = (char*)TAURARO_ALLOC(96);
+    snprintf(m, 96, "ValueError: attempt to assign sequence of size %lld to extended slice of size %lld", got, want);
+    _tr_exc_raise(m);
+}
+#define _TR_SL_KEEP(x) (x)
+#define _TR_SL_NONE(x) ((void)0)
+#define _TR_SL_OBJ_RELEASE(x) _tr_obj_release((x), drop)
+#define _TR_SL_NO_PARAM
+#define _TR_SL_DROP_PARAM , void (*drop)(void*)
+#define _TR_LIST_SLICE_OPS(LT, FN, T, RETAIN, RELEASE, XPARAM) \
+static void FN##_set_slice(LT* l, long long start, long long stop, long long step, LT* v XPARAM) { \
+    if (!l) return; \
+    long long n = (long long)l->len, cnt = _tr_slice_adjust(n, &start, &stop, step); \
+    long long k = v ? (long long)v->len : 0; \
+    if (step != 1 && k != cnt) _tr_slice_size_error(k, cnt); \
+    T* tmp = (T*)malloc(sizeof(T) * (size_t)(k > 0 ? k : 1)); \
+    for (long long i = 0; i < k; i++) tmp[i] = RETAIN(v->data[i]); \
+    if (step == 1) { \
+        long long old = stop > start ? stop - start : 0, nl = n - old + k; \
+        for (long long i = start; i < start + old; i++) RELEASE(l->data[i]); \
+        if ((size_t)nl > l->capacity) { l->capacity = (size_t)nl; l->data = (T*)realloc(l->data, sizeof(T) * l->capacity); } \
+        memmove(&l->data[start + k], &l->data[start + old], sizeof(T) * (size_t)(n - start - old)); \
+        if (k > 0) memcpy(&l->data[start], tmp, sizeof(T) * (size_t)k); \
+        l->len = (size_t)nl; \
+    } else { \
+        for (long long i = 0; i < k; i++) { RELEASE(l->data[start + i * step]); l->data[start + i * step] = tmp[i]; } \
+    } \
+    free(tmp); \
+} \
+static LT* FN##_slice(LT* l, long long start, long long stop, long long step XPARAM) { \
+    LT* r = LT##_new(); \
+    if (!l) return r; \
+    long long cnt = _tr_slice_adjust((long long)l->len, &start, &stop, step); \
+    if ((size_t)cnt > r->capacity) { r->capacity = (size_t)cnt; r->data = (T*)realloc(r->data, sizeof(T) * r->capacity); } \
+    for (long long i = 0; i < cnt; i++) r->data[i] = RETAIN(l->data[start + i * step]); \
+    r->len = (size_t)cnt; \
+    return r; \
+} \
+static void FN##_del_slice(LT* l, long long start, long long stop, long long step XPARAM) { \
+    if (!l) return; \
+    long long n = (long long)l->len, cnt = _tr_slice_adjust(n, &start, &stop, step); \
+    if (cnt == 0) return; \
+    if (step < 0) { start += (cnt - 1) * step; step = -step; } \
+    long long end = start + (cnt - 1) * step, w = start; \
+    for (long long r = start; r < n; r++) { \
+        if (r <= end && (r - start) % step == 0) { RELEASE(l->data[r]); continue; } \
+        l->data[w++] = l->data[r]; \
+    } \
+    l->len = (size_t)w; \
+}
+_TR_LIST_SLICE_OPS(List_i64, List_i64, long long, _TR_SL_KEEP, _TR_SL_NONE, _TR_SL_NO_PARAM)
+_TR_LIST_SLICE_OPS(List_f64, List_f64, double, _TR_SL_KEEP, _TR_SL_NONE, _TR_SL_NO_PARAM)
+_TR_LIST_SLICE_OPS(List_str, List_str, char*, _TR_SL_KEEP, _TR_SL_NONE, _TR_SL_NO_PARAM)
+_TR_LIST_SLICE_OPS(List_TrStr, List_TrStr, TrStr, _tr_str_retain, _tr_str_release, _TR_SL_NO_PARAM)
+_TR_LIST_SLICE_OPS(List_ptr, List_ptr, void*, _TR_SL_KEEP, _TR_SL_NONE, _TR_SL_NO_PARAM)
+_TR_LIST_SLICE_OPS(List_ptr, List_ptr_obj, void*, _tr_obj_retain, _TR_SL_OBJ_RELEASE, _TR_SL_DROP_PARAM)
+_TR_LIST_SLICE_OPS(List_bool, List_bool, _Bool, _TR_SL_KEEP, _TR_SL_NONE, _TR_SL_NO_PARAM)
+_TR_LIST_SLICE_OPS(List_i8, List_i8, int8_t, _TR_SL_KEEP, _TR_SL_NONE, _TR_SL_NO_PARAM)
+_TR_LIST_SLICE_OPS(List_i32, List_i32, int, _TR_SL_KEEP, _TR_SL_NONE, _TR_SL_NO_PARAM)
+_TR_LIST_SLICE_OPS(List_char, List_char, char, _TR_SL_KEEP, _TR_SL_NONE, _TR_SL_NO_PARAM)
+_TR_LIST_SLICE_OPS(List_u8, List_u8, uint8_t, _TR_SL_KEEP, _TR_SL_NONE, _TR_SL_NO_PARAM)
+_TR_LIST_SLICE_OPS(List_u32, List_u32, uint32_t, _TR_SL_KEEP, _TR_SL_NONE, _TR_SL_NO_PARAM)
+/* xs == ys for lists of scalars or strings: same length and pairwise-equal
+ * elements. A float NaN never equals itself, as with ==. */
+#define _TR_ELEM_EQ(a, b) ((a) == (b))
+#define _TR_CSTR_EQ(a, b) ((a) == (b) || ((a) && (b) && strcmp((a), (b)) == 0))
+#define _TR_TRSTR_EQ(a, b) _TR_CSTR_EQ((a).data, (b).data)
+#define _TR_LIST_EQ(LT, EQ) \
+static inline _Bool LT##_eq(LT* a, LT* b) { \
+    if (a == b) return 1; \
+    size_t n = a ? a->len : 0; \
+    if (n != (b ? b->len : 0)) return 0; \
+    for (size_t i = 0; i < n; i++) if (!EQ(a->data[i], b->data[i])) return 0; \
+    return 1; \
+}
+_TR_LIST_EQ(List_i64, _TR_ELEM_EQ)
+_TR_LIST_EQ(List_f64, _TR_ELEM_EQ)
+_TR_LIST_EQ(List_str, _TR_CSTR_EQ)
+_TR_LIST_EQ(List_TrStr, _TR_TRSTR_EQ)
+_TR_LIST_EQ(List_bool, _TR_ELEM_EQ)
+_TR_LIST_EQ(List_i8, _TR_ELEM_EQ)
+_TR_LIST_EQ(List_i32, _TR_ELEM_EQ)
+_TR_LIST_EQ(List_char, _TR_ELEM_EQ)
+_TR_LIST_EQ(List_u8, _TR_ELEM_EQ)
+_TR_LIST_EQ(List_u32, _TR_ELEM_EQ)
+
+/* ── Byte lists: str.encode(), List[int].decode() ───────────────────────── */
+/* Binary data is a List[int] of values 0-255 (the same convention std.compress
+ * uses), so it survives NUL and 0x80-0xFF bytes that a `str` round trip would
+ * cut short or mangle. Codecs are UTF-8 (the default), ASCII and Latin-1, with
+ * CPython's error messages. */
+static inline uint8_t* _tr_bytes_pack(List_i64* l) {
+    size_t n = l ? l->len : 0;
+    uint8_t* b = (uint8_t*)malloc(n > 0 ? n : 1);
+    for (size_t i = 0; i < n; i++) {
+        if (l->data[i] < 0 || l->data[i] > 255) { free(b); _tr_exc_raise((char*)"ValueError: bytes must be in range(0, 256)"); }
+        b[i] = (uint8_t)l->data[i];
+    }
+    return b;
+}
+static inline List_i64* _tr_bytes_unpack(const uint8_t* b, size_t n) {
+    List_i64* l = List_i64_new();
+    for (size_t i = 0; i < n; i++) List_i64_append(l, (long long)b[i]);
+    return l;
+}
+/* 0 = utf-8, 1 = ascii, 2 = latin-1; anything else is a LookupError. */
+static int _tr_codec(const char* enc) {
+    char k[16]; size_t j = 0;
+    for (const char* p = enc ? enc : "utf-8"; *p; p++) {
+        if (*p == '-' || *p == '_' || *p == ' ') continue;
+        if (j + 1 >= sizeof k) { j = 0; break; }
+        k[j++] = (char)tolower((unsigned char)*p);
+    }
+    k[j] = '\0';
+    if (!strcmp(k, "utf8") || !strcmp(k, "u8")) return 0;
+    if (!strcmp(k, "ascii") || !strcmp(k, "usascii")) return 1;
+    if (!strcmp(k, "latin1") || !strcmp(k, "iso88591") || !strcmp(k, "l1")) return 2;
+    char* m = (char*)TAURARO_ALLOC(strlen(enc) + 40);
+    sprintf(m, "LookupError: unknown encoding: %s", enc);
+    _tr_exc_raise(m);
+    return 0;
+}
+static const char* const _tr_codec_names[] = { "utf-8", "ascii", "latin-1" };
+static inline void _tr_codec_error(const char* kind, int codec, const char* what, size_t a, size_t b, const char* why) {
+    char* m = (char*)TAURARO_ALLOC(160);
+    if (a == b) snprintf(m, 160, "%s: '%s' codec can't %s in position %zu: %s", kind, _tr_codec_names[codec], what, a, why);
+    else snprintf(m, 160, "%s: '%s' codec can't %s in position %zu-%zu: %s", kind, _tr_codec_names[codec], what, a, b, why);
+    _tr_exc_raise(m);
+}
+static char* _tr_bytes_decode(List_i64* l, char* enc) {
+    int codec = _tr_codec(enc);
+    size_t n = l ? l->len : 0;
+    uint8_t* b = _tr_bytes_pack(l);
+    for (size_t i = 0; i < n; i++)
+        if (b[i] == 0) { free(b); _tr_exc_raise((char*)"ValueError: embedded null byte"); }
+    char* out = NULL;
+    if (codec == 2) {
+        out = (char*)_tr_checked_alloc(n * 2 + 1);
+        size_t k = 0;
+        for (size_t i = 0; i < n; i++) {
+            if (b[i] < 0x80) out[k++] = (char)b[i];
+            else { out[k++] = (char)(0xC0 | (b[i] >> 6)); out[k++] = (char)(0x80 | (b[i] & 0x3F)); }
+        }
+        out[k] = '\0';
+        free(b);
+        return out;
+    }
+    char what[40];
+    for (size_t i = 0; i < n; ) {
+        uint8_t c = b[i];
+        if (c < 0x80) { i++; continue; }
+        if (codec == 1) {
+            snprintf(what, sizeof what, "decode byte 0x%02x", c); free(b);
+            _tr_codec_error("UnicodeDecodeError", 1, what, i, i, "ordinal not in range(128)");
+        }
+        int need = 0; uint8_t lo = 0x80, hi = 0xBF;
+        if (c >= 0xC2 && c <= 0xDF) need = 1;
+        else if (c >= 0xE0 && c <= 0xEF) { need = 2; if (c == 0xE0) lo = 0xA0; if (c == 0xED) hi = 0x9F; }
+        else if (c >= 0xF0 && c <= 0xF4) { need = 3; if (c == 0xF0) lo = 0x90; if (c == 0xF4) hi = 0x8F; }
+        else {
+            snprintf(what, sizeof what, "decode byte 0x%02x", c); free(b);
+            _tr_codec_error("UnicodeDecodeError", 0, what, i, i, "invalid start byte");
+        }
+        for (size_t k = 1; k <= (size_t)need; k++) {
+            const char* why = NULL;
+            if (i + k >= n) why = "unexpected end of data";
+            else if (b[i + k] < (k == 1 ? lo : 0x80) || b[i + k] > (k == 1 ? hi : 0xBF)) why = "invalid continuation byte";
+            if (!why) continue;
+            if (k == 1) snprintf(what, sizeof what, "decode byte 0x%02x", c);
+            else snprintf(what, sizeof what, "decode bytes");
+            free(b);
+            _tr_codec_error("UnicodeDecodeError", 0, what, i, i + k - 1, why);
+        }
+        i += (size_t)need + 1;
+    }
+    out = (char*)_tr_checked_alloc(n + 1);
+    if (n > 0) memcpy(out, b, n);
+    out[n] = '\0';
+    free(b);
+    return out;
+}
+/* Byte-list counterparts of the str search methods: find, starts_with,
+ * ends_with, split, replace and join, as bytes.find() etc. do in Python.
+ * Bytes are compared as the list's values, so every byte survives, and each
+ * result is a fresh list. */
+static inline _Bool _tr_bytes_at(List_i64* h, size_t i, List_i64* n) {
+    if (i + n->len > h->len) return 0;
+    for (size_t k = 0; k < n->len; k++) if (h->data[i + k] != n->data[k]) return 0;
+    return 1;
+}
+static long long _tr_bytes_find(List_i64* h, List_i64* n, long long start) {
+    long long hl = (long long)h->len;
+    if (start < 0) { start += hl; if (start < 0) start = 0; }
+    for (long long i = start; i + (long long)n->len <= hl; i++)
+        if (_tr_bytes_at(h, (size_t)i, n)) return i;
+    return -1;
+}
+static _Bool _tr_bytes_starts_with(List_i64* h, List_i64* p) { return _tr_bytes_at(h, 0, p); }
+static _Bool _tr_bytes_ends_with(List_i64* h, List_i64* p) {
+    return p->len <= h->len && _tr_bytes_at(h, h->len - p->len, p);
+}
+static inline List_i64* _tr_bytes_range(List_i64* h, size_t a, size_t b) {
+    List_i64* out = List_i64_new();
+    for (size_t i = a; i < b; i++) List_i64_append(out, h->data[i]);
+    return out;
+}
+static inline _Bool _tr_bytes_space(long long c) { return c == 32 || (c >= 9 && c <= 13); }
+/* split(sep[, maxsplit]): sep == NULL splits on runs of ASCII whitespace and
+ * drops empty fields, as bytes.split() with no separator does. */
+static List_ptr* _tr_bytes_split(List_i64* h, List_i64* sep, long long maxsplit) {
+    List_ptr* out = List_ptr_new();
+    size_t i = 0, n = h->len;
+    if (sep) {
+        if (sep->len == 0) { List_ptr_free(out); _tr_exc_raise((char*)"ValueError: empty separator"); }
+        size_t from = 0;
+        while (maxsplit != 0 && i + sep->len <= n) {
+            if (_tr_bytes_at(h, i, sep)) {
+                List_ptr_append(out, _tr_bytes_range(h, from, i));
+                i += sep->len; from = i;
+                if (maxsplit > 0) maxsplit--;
+            } else i++;
+        }
+        List_ptr_append(out, _tr_bytes_range(h, from, n));
+        return out;
+    }
+    for (;;) {
+        while (i < n && _tr_bytes_space(h->data[i])) i++;
+        if (i == n) break;
+        if (maxsplit == 0) {
+            size_t end = n;
+            while (end > i && _tr_bytes_space(h->data[end - 1])) end--;
+            List_ptr_append(out, _tr_bytes_range(h, i, end));
+            break;
+        }
+        size_t from = i;
+        while (i < n && !_tr_bytes_space(h->data[i])) i++;
+        List_ptr_append(out, _tr_bytes_range(h, from, i));
+        if (maxsplit > 0) maxsplit--;
+    }
+    return out;
+}
+/* replace(old, new[, count]): an empty `old` matches before every byte and at
+ * the end, as in Python (b"ab".replace(b"", b"-") == b"-a-b-"). */
+static List_i64* _tr_bytes_replace(List_i64* h, List_i64* old, List_i64* nw, long long count) {
+    List_i64* out = List_i64_new();
+    size_t i = 0, n = h->len;
+    for (;;) {
+        if (count != 0 && _tr_bytes_at(h, i, old)) {
+            for (size_t k = 0; k < nw->len; k++) List_i64_append(out, nw->data[k]);
+            if (count > 0) count--;
+            if (old->len > 0) { i += old->len; continue; }
+        }
+        if (i >= n) break;
+        List_i64_append(out, h->data[i++]);
+    }
+    return out;
+}
+/* sep.join(parts): the parts with `sep` between them. */
+static List_i64* _tr_bytes_join(List_i64* sep, List_ptr* parts) {
+    List_i64* out = List_i64_new();
+    for (size_t p = 0; parts && p < parts->len; p++) {
+        if (p > 0) for (size_t k = 0; k < sep->len; k++) List_i64_append(out, sep->data[k]);
+        List_i64* part = (List_i64*)parts->data[p];
+        for (size_t k = 0; part && k < part->len; k++) List_i64_append(out, part->data[k]);
+    }
+    return out;
+}
+/* File.read_bytes(path) / File.write_bytes(path, data): the file's exact bytes.
+ * An unreadable file reads as an empty list, like File.read_text's "".
+ * std-tier only (FILE/fopen). */
+#ifndef TAURARO_BARE
+static List_i64* _tr_file_read_bytes(char* path) {
+    List_i64* l = List_i64_new();
+    FILE* fp = path ? fopen(path, "rb") : NULL;
+    if (!fp) return l;
+    uint8_t buf[4096]; size_t got;
+    while ((got = fread(buf, 1, sizeof buf, fp)) > 0)
+        for (size_t i = 0; i < got; i++) List_i64_append(l, (long long)buf[i]);
+    fclose(fp);
+    return l;
+}
+static _Bool _tr_file_write_bytes(char* path, List_i64* data, char* mode) {
+    uint8_t* b = _tr_bytes_pack(data);
+    FILE* fp = path ? fopen(path, mode) : NULL;
+    if (!fp) { free(b); return 0; }
+    size_t n = data ? data->len : 0;
+    _Bool ok = fwrite(b, 1, n, fp) == n;
+    free(b);
+    return fclose(fp) == 0 && ok;
+}
+#else
+static List_i64* _tr_file_read_bytes(char* path) { (void)path; return List_i64_new(); }
+static _Bool _tr_file_write_bytes(char* path, List_i64* data, char* mode) { (void)path; (void)data; (void)mode; return 0; }
+#endif
+
+/* ── open() and the file object (std.io.file) ───────────────────────────── */
+/* A mode string packed into bits: 1 read, 2 write, 4 binary, 8 append,
+ * 16 exclusive create, 32 update ('+'). A strict parse (open) rejects what
+ * io.open rejects, with its messages. A lenient one (File.init) never raises:
+ * a mode it can't read allows both reading and writing, and fopen decides. */
+static long long _tr_file_mode_flags(char* mode, _Bool strict) {
+    const char* m = mode ? mode : "";
+    long long f = 0; int seen = 0, kinds = 0, text = 0, bad = 0;
+    for (const char* p = m; *p && !bad; p++) {
+        int bit;
+        switch (*p) {
+        case 'r': bit = 1;  f |= 1; kinds++; break;
+        case 'w': bit = 2;  f |= 2; kinds++; break;
+        case 'a': bit = 4;  f |= 2 | 8; kinds++; break;
+        case 'x': bit = 8;  f |= 2 | 16; kinds++; break;
+        case '+': bit = 16; f |= 1 | 2 | 32; break;
+        case 'b': bit = 32; f |= 4; break;
+        case 't': bit = 64; text = 1; break;
+        default:  bit = 0; bad = 1; break;
+        }
+        if (seen & bit) bad = 1;
+        seen |= bit;
+    }
+    if (!strict) return (bad || kinds != 1) ? (f | 1 | 2) : f;
+    const char* why = NULL;
+    if (bad) {
+        size_t n = strlen(m) + 32;
+        char* e = (char*)TAURARO_ALLOC(n);
+        snprintf(e, n, "ValueError: invalid mode: '%s'", m);
+        _tr_exc_raise(e);
+    }
+    if (text && (f & 4)) why = "can't have text and binary mode at once";
+    else if (kinds > 1) why = "must have exactly one of create/read/write/append mode";
+    else if (kinds == 0) why = "Must have exactly one of create/read/write/append mode and at most one plus";
+    if (why) {
+        size_t n = strlen(why) + 16;
+        char* e = (char*)TAURARO_ALLOC(n);
+        snprintf(e, n, "ValueError: %s", why);
+        _tr_exc_raise(e);
+    }
+    return f;
+}
+#ifndef TAURARO_BARE
+/* errno from a failed open as the matching OSError subclass, worded like
+ * CPython: "FileNotFoundError: [Errno 2] No such file or directory: 'p'". */
+static void _tr_file_os_error(int err, const char* path) {
+    const char* kind = err == ENOENT ? "FileNotFoundError"
+                     : err == EEXIST ? "FileExistsError"
+                     : (err == EACCES || err == EPERM) ? "PermissionError"
+                     : err == EISDIR ? "IsADirectoryError"
+                     : err == ENOTDIR ? "NotADirectoryError" : "OSError";
+    const char* why = strerror(err);
+    size_t n = strlen(kind) + strlen(why) + strlen(path) + 32;
+    char* m = (char*)TAURARO_ALLOC(n);
+    snprintf(m, n, "%s: [Errno %d] %s: '%s'", kind, err, why, path);
+    _tr_exc_raise(m);
+}
+/* open(path, mode): the C stream, or a raise. Streams are always opened in
+ * C's binary mode; text mode's newline handling is done by the readers. */
+static void* _tr_file_open(char* path, char* mode) {
+    long long f = _tr_file_mode_flags(mode, 1);
+    char cm[6]; int k = 0;
+    char base = 'r';
+    for (const char* p = mode; *p; p++)
+        if (*p == 'r' || *p == 'w' || *p == 'a' || *p == 'x') { base = *p == 'x' ? 'w' : *p; break; }
+    cm[k++] = base;
+    if (f & 32) cm[k++] = '+';
+    cm[k++] = 'b';
+    if (f & 16) cm[k++] = 'x';
+    cm[k] = '\0';
+    const char* pth = path ? path : "";
+    FILE* fp = fopen(pth, cm);
+    int err = fp ? 0 : errno;
+#ifndef _WIN32
+    struct stat st;
+    if (fp && fstat(fileno(fp), &st) == 0 && S_ISDIR(st.st_mode)) { fclose(fp); fp = NULL; err = EISDIR; }
+#endif
+    if (!fp) _tr_file_os_error(err, pth);
+    return fp;
+}
+/* Up to `limit` bytes (all of them when limit < 0) from fp, stopping after
+ * the first '\n' when `line` is set; "" at end of file. Text mode reads "\r\n"
+ * and a lone '\r' as '\n', like Python's universal newlines. OWNED heap. */
+static char* _tr_file_read_str(void* fp, long long limit, _Bool text, _Bool line) {
+    size_t cap = 64, n = 0;
+    char* s = (char*)_tr_checked_alloc(cap);
+    int c;
+    while ((limit < 0 || (long long)n < limit) && (c = getc((FILE*)fp)) != EOF) {
+        if (text && c == '\r') {
+            int d = getc((FILE*)fp);
+            if (d != '\n' && d != EOF) ungetc(d, (FILE*)fp);
+            c = '\n';
+        }
+        if (n + 2 > cap) { cap *= 2; s = (char*)TAURARO_REALLOC(s, cap); }
+        s[n++] = (char)c;
+        if (line && c == '\n') break;
+    }
+    s[n] = '\0';
+    return s;
+}
+/* Binary reads and writes as byte lists (elements 0-255). */
+static List_i64* _tr_file_read_list(void* fp, long long limit) {
+    List_i64* l = List_i64_new();
+    uint8_t buf[4096];
+    while (limit < 0 || (long long)l->len < limit) {
+        size_t want = sizeof buf;
+        if (limit >= 0 && (unsigned long long)(limit - (long long)l->len) < want) want = (size_t)(limit - (long long)l->len);
+        size_t got = fread(buf, 1, want, (FILE*)fp);
+        for (size_t i = 0; i < got; i++) List_i64_append(l, (long long)buf[i]);
+        if (got < want) break;
+    }
+    return l;
+}
+static long long _tr_file_write_list(void* fp, List_i64* data) {
+    uint8_t* b = _tr_bytes_pack(data);
+    size_t n = fwrite(b, 1, data ? data->len : 0, (FILE*)fp);
+    free(b);
+    return (long long)n;
+}
+#else
+static void* _tr_file_open(char* path, char* mode) {
+    (void)path; _tr_file_mode_flags(mode, 1);
+    _tr_exc_raise((char*)"OSError: file I/O is not available in a freestanding build");
+    return NULL;
+}
+static char* _tr_file_read_str(void* fp, long long limit, _Bool text, _Bool line) { (void)fp; (void)limit; (void)text; (void)line; return _tr_empty_heap_str(); }
+static List_i64* _tr_file_read_list(void* fp, long long limit) { (void)fp; (void)limit; return List_i64_new(); }
+static long long _tr_file_write_list(void* fp, List_i64* data) { (void)fp; (void)data; return 0; }
+#endif
+
 
 typedef struct { long long* data; size_t len; size_t capacity; } Set_i64;
 static inline Set_i64* Set_i64_new(void) { Set_i64* l=(Set_i64*)malloc(sizeof(Set_i64)); l->data=(long long*)malloc(sizeof(long long)*8); l->len=0; l->capacity=8; return l; }
@@ -4210,6 +5422,72 @@ static inline long long Set_str_len(Set_str* l) { return l ? (long long)l->len :
 static inline _Bool Set_str_is_empty(Set_str* l) { return !l || l->len==0; }
 static inline void Set_str_remove(Set_str* l, const char* v) { if(!l||!v) return; for(size_t i=0;i<l->len;i++) if(l->data[i]&&strcmp(l->data[i],v)==0){ for(size_t j=i;j+1<l->len;j++) l->data[j]=l->data[j+1]; l->len--; return; } }
 
+/* ── range objects ──────────────────────────────────────────────────────── */
+/* A range is a (start, stop, step) triple passed by value; nothing is ever
+   materialised. len, indexing and membership are plain arithmetic, and
+   `for x in r` compiles to a counted C loop over the same three fields. */
+typedef struct { long long start; long long stop; long long step; } TrRange;
+
+static inline TrRange _tr_range(long long start, long long stop, long long step) {
+    if (step == 0) _tr_exc_raise((char*)"ValueError: range() arg 3 must not be zero");
+    TrRange r = { start, stop, step };
+    return r;
+}
+static inline long long _tr_range_len(TrRange r) {
+    if (r.step > 0) return r.start < r.stop ? (r.stop - r.start - 1) / r.step + 1 : 0;
+    return r.start > r.stop ? (r.start - r.stop - 1) / (-r.step) + 1 : 0;
+}
+static inline long long _tr_range_get(TrRange r, long long i) {
+    long long n = _tr_range_len(r);
+    if (i < 0) i += n;
+    if (i < 0 || i >= n) _tr_exc_raise((char*)"IndexError: range object index out of range");
+    return r.start + i * r.step;
+}
+static inline _Bool _tr_range_contains(TrRange r, long long x) {
+    if (r.step > 0) { if (x < r.start || x >= r.stop) return 0; }
+    else if (x > r.start || x <= r.stop) return 0;
+    return (x - r.start) % r.step == 0;
+}
+/* Ranges compare as the sequences they produce: range(0) == range(5, 5),
+   and range(0, 3, 2) == range(0, 4, 2). */
+static inline _Bool _tr_range_eq(TrRange a, TrRange b) {
+    long long n = _tr_range_len(a);
+    if (n != _tr_range_len(b)) return 0;
+    if (n == 0) return 1;
+    if (a.start != b.start) return 0;
+    return n == 1 || a.step == b.step;
+}
+/* r.slice(lo, hi): Python's r[lo:hi] with step 1, indices clamped. */
+static inline TrRange _tr_range_slice(TrRange r, long long lo, long long hi) {
+    long long n = _tr_range_len(r);
+    if (lo < 0) { lo += n; if (lo < 0) lo = 0; } else if (lo > n) lo = n;
+    if (hi < 0) { hi += n; if (hi < 0) hi = 0; } else if (hi > n) hi = n;
+    if (hi < lo) hi = lo;
+    TrRange s = { r.start + lo * r.step, r.start + hi * r.step, r.step };
+    return s;
+}
+static inline long long _tr_range_index(TrRange r, long long x) {
+    if (!_tr_range_contains(r, x)) {
+        char* m = (char*)TAURARO_ALLOC(64);
+        snprintf(m, 64, "ValueError: %lld is not in range", x);
+        _tr_exc_raise(m);
+    }
+    return (x - r.start) / r.step;
+}
+static inline long long _tr_range_count(TrRange r, long long x) { return _tr_range_contains(r, x) ? 1 : 0; }
+static inline List_i64* _tr_range_to_list(TrRange r) {
+    List_i64* l = List_i64_new();
+    long long n = _tr_range_len(r);
+    for (long long i = 0; i < n; i++) List_i64_append(l, r.start + i * r.step);
+    return l;
+}
+static inline char* _tr_range_str(TrRange r) {
+    char* b = (char*)TAURARO_ALLOC(80);
+    if (r.step == 1) snprintf(b, 80, "range(%lld, %lld)", r.start, r.stop);
+    else snprintf(b, 80, "range(%lld, %lld, %lld)", r.start, r.stop, r.step);
+    return b;
+}
+
 /* ── Bounds-checked list access ─────────────────────────────────────────── */
 static inline List_i64* _tr_range_new(long long start, long long stop, bool inclusive) {
     List_i64* l = List_i64_new();
@@ -4409,22 +5687,154 @@ static inline List_TrStr* _tr_str_split(const char* s, const char* sep) {
     _tr_free(cp); return l;
 }
 static inline List_TrStr* _tr_str_lines(const char* s) { return _tr_str_split(s, "\n"); }
-/* s.format(a, b, ...): replace each "{}" placeholder in order with the given (already
- * stringified) arguments. Extra placeholders / args are ignored. */
+/* s.splitlines(keepends): split on every line boundary Python recognises —
+ * \n, \r, \r\n, \v, \f, \x1c-\x1e, U+0085, U+2028 and U+2029. A trailing
+ * boundary does not produce an empty last line; keepends keeps each break. */
+static inline List_TrStr* _tr_str_splitlines(const char* s, bool keepends) {
+    List_TrStr* l = List_TrStr_new(); if (!s) return l;
+    const unsigned char* p = (const unsigned char*)s; const unsigned char* start = p;
+    while (*p) {
+        size_t brk = 0;
+        if (*p == '\r') brk = (p[1] == '\n') ? 2 : 1;
+        else if (*p == '\n' || *p == '\v' || *p == '\f' || (*p >= 0x1c && *p <= 0x1e)) brk = 1;
+        else if (p[0] == 0xC2 && p[1] == 0x85) brk = 2;
+        else if (p[0] == 0xE2 && p[1] == 0x80 && (p[2] == 0xA8 || p[2] == 0xA9)) brk = 3;
+        if (!brk) { p++; continue; }
+        size_t n = (size_t)(p - start) + (keepends ? brk : 0);
+        char* line = (char*)malloc(n + 1); memcpy(line, start, n); line[n] = '\0';
+        List_TrStr_append_owned(l, _tr_str_wrap(line));
+        p += brk; start = p;
+    }
+    if (p > start) List_TrStr_append_owned(l, _tr_str_wrap(strdup((const char*)start)));
+    return l;
+}
+/* s.format(a, b, ...) on a format string only known at run time: "{}" takes the
+ * next argument, "{N}" argument N, "{{" / "}}" are literal braces. The arguments
+ * are already stringified. Out-of-range fields are left empty. (A literal format
+ * string is rewritten into an f-string by the compiler instead.) */
 static inline char* _tr_str_format(const char* fmt, const char* const* args, long long argc) {
     if (!fmt) fmt = "";
     size_t cap = strlen(fmt);
     for (long long i = 0; i < argc; i++) if (args[i]) cap += strlen(args[i]);
+    for (const char* q = fmt; *q; q++) if (q[0] == '{' && q[1] >= '0' && q[1] <= '9')
+        for (long long i = 0; i < argc; i++) if (args[i]) cap += strlen(args[i]);
     char* r = (char*)_tr_checked_alloc(cap + 1);
     char* w = r; const char* p = fmt; long long ai = 0;
     while (*p) {
-        if (p[0] == '{' && p[1] == '}' && ai < argc) {
-            const char* a = args[ai++]; if (a) { size_t l = strlen(a); memcpy(w, a, l); w += l; }
-            p += 2;
-        } else { *w++ = *p++; }
+        if ((p[0] == '{' && p[1] == '{') || (p[0] == '}' && p[1] == '}')) { *w++ = *p; p += 2; continue; }
+        if (p[0] == '{') {
+            const char* q = p + 1; long long idx = -1;
+            if (*q >= '0' && *q <= '9') { idx = 0; while (*q >= '0' && *q <= '9') idx = idx * 10 + (*q++ - '0'); }
+            if (*q == '}') {
+                if (idx < 0) idx = ai++;
+                const char* a = (idx < argc) ? args[idx] : NULL;
+                if (a) { size_t l = strlen(a); memcpy(w, a, l); w += l; }
+                p = q + 1; continue;
+            }
+        }
+        *w++ = *p++;
     }
     *w = '\0'; return r;
 }
+static inline int _tr_utf8_encode_cp(uint32_t cp, char* buf);
+/* format(x, spec) for a spec only known at run time (`"{:{w}}".format(x, w=8)`):
+ * Python's [[fill]align][sign][#][0][width][,|_][.precision][type] applied to an
+ * int (kind 0), float (kind 1) or str (kind 2). Returns a malloc'd string.
+ * 'c' formats an int as the character with that code point; '#' keeps the
+ * decimal point (and for g/G the trailing zeros) of a float. */
+static inline char* _tr_fmt_spec(int kind, long long iv, double fv, const char* sv, const char* spec) {
+    if (!spec) spec = "";
+    if (!sv) sv = "";
+    const char* p = spec;
+    char fill = ' ', align = 0, sign = '-', grp = 0, type = 0; int alt = 0, zero = 0; long long width = 0, prec = -1;
+    if (p[0] && (p[1] == '<' || p[1] == '>' || p[1] == '^' || p[1] == '=')) { fill = p[0]; align = p[1]; p += 2; }
+    else if (p[0] == '<' || p[0] == '>' || p[0] == '^' || p[0] == '=') align = *p++;
+    if (*p == '+' || *p == '-' || *p == ' ') sign = *p++;
+    if (*p == '#') { alt = 1; p++; }
+    if (*p == '0') { if (!align) { fill = '0'; align = '='; zero = 1; } p++; }
+    while (*p >= '0' && *p <= '9' && width < 100000) width = width * 10 + (*p++ - '0');
+    if (*p == ',' || *p == '_') grp = *p++;
+    if (*p == '.') { p++; prec = 0; while (*p >= '0' && *p <= '9' && prec < 1000) prec = prec * 10 + (*p++ - '0'); }
+    if (*p) type = *p;
+    if (type == 'c') {
+        if (kind != 0) _tr_exc_raise(kind == 1 ? (char*)"ValueError: Unknown format code 'c' for object of type 'float'" : (char*)"ValueError: Unknown format code 'c' for object of type 'str'");
+        if (sign != '-') _tr_exc_raise((char*)"ValueError: Sign not allowed with integer format specifier 'c'");
+        if (alt) _tr_exc_raise((char*)"ValueError: Alternate form (#) not allowed with integer format specifier 'c'");
+        if (iv < 0 || iv > 0x10FFFF) _tr_exc_raise((char*)"OverflowError: %c arg not in range(0x110000)");
+    }
+    if (kind == 0 && (type == 'e' || type == 'E' || type == 'f' || type == 'F' || type == 'g' || type == 'G' || type == '%')) { kind = 1; fv = (double)iv; }
+    char body[1200]; const char* pfx = ""; int neg = 0; size_t blen;
+    if (kind == 2) {
+        blen = strlen(sv);
+        if (prec >= 0 && (size_t)prec < blen) blen = (size_t)prec;
+        if (!align) align = '<';
+    } else {
+        if (kind == 0) {
+            neg = iv < 0;
+            unsigned long long m = neg ? (unsigned long long)(-(iv + 1)) + 1ULL : (unsigned long long)iv;
+            if (type == 'c') { body[_tr_utf8_encode_cp((uint32_t)iv, body)] = '\0'; neg = 0; }
+            else if (type == 'x' || type == 'X') { snprintf(body, sizeof body, type == 'x' ? "%llx" : "%llX", m); if (alt) pfx = type == 'x' ? "0x" : "0X"; }
+            else if (type == 'o') { snprintf(body, sizeof body, "%llo", m); if (alt) pfx = "0o"; }
+            else if (type == 'b') {
+                char tmp[65]; int n = 0;
+                do { tmp[n++] = (char)('0' + (m & 1ULL)); m >>= 1; } while (m);
+                for (int i = 0; i < n; i++) body[i] = tmp[n - 1 - i];
+                body[n] = '\0'; if (alt) pfx = "0b";
+            } else snprintf(body, sizeof body, "%llu", m);
+        } else {
+            neg = fv < 0 || (fv == 0 && 1.0 / fv < 0);
+            double a = neg ? -fv : fv;
+            int pr = prec < 0 ? 6 : (int)prec;
+            char cf[6] = "%.*f"; char* ct = cf + 3;
+            if (alt) { memcpy(cf, "%#.*f", 6); ct = cf + 4; }
+            if (type == 'f' || type == 'F' || type == 'e' || type == 'E' || type == 'g' || type == 'G') { *ct = type; snprintf(body, sizeof body, cf, pr, a); }
+            else if (type == '%') { snprintf(body, sizeof body - 1, cf, pr, a * 100.0); strcat(body, "%"); }
+            else if (prec >= 0) { *ct = 'g'; snprintf(body, sizeof body, cf, pr, a); }
+            else _tr_float_repr_into(a, 0, body, sizeof body);
+        }
+        if (grp) {
+            /* Group the leading digit run: every 3 for decimal, every 4 for x/o/b. */
+            size_t nd = 0; while (body[nd] >= '0' && body[nd] <= '9') nd++;
+            if (type == 'x' || type == 'X') while (isxdigit((unsigned char)body[nd])) nd++;
+            int every = (type == 'x' || type == 'X' || type == 'o' || type == 'b') ? 4 : 3;
+            /* With the '0' flag the padding zeros are digits too and get grouped
+             * (format(7, "012,") == "0,000,000,007"): widen the run first. */
+            if (zero && width > 0) {
+                size_t fixed = (neg || sign == '+' || sign == ' ') + strlen(pfx) + strlen(body + nd);
+                size_t want = nd;
+                while (fixed + want + (want - 1) / (size_t)every < (size_t)width && want < 400) want++;
+                if (want > nd && strlen(body) + (want - nd) < sizeof body / 2) {
+                    memmove(body + (want - nd), body, strlen(body) + 1);
+                    memset(body, '0', want - nd); nd = want;
+                }
+            }
+            char g[1200]; size_t gi = 0;
+            for (size_t i = 0; i < nd && gi < sizeof g - 2; i++) {
+                if (i > 0 && (nd - i) % (size_t)every == 0) g[gi++] = grp;
+                g[gi++] = body[i];
+            }
+            size_t rest = strlen(body + nd);
+            if (gi + rest < sizeof g) { memcpy(g + gi, body + nd, rest + 1); memcpy(body, g, gi + rest + 1); }
+        }
+        blen = strlen(body); sv = body;
+        if (!align) align = '>';
+    }
+    const char* sg = neg ? "-" : (kind != 2 && sign == '+') ? "+" : (kind != 2 && sign == ' ') ? " " : "";
+    size_t sl = strlen(sg), pl = strlen(pfx), used = sl + pl + blen;
+    size_t pad = (width > 0 && (size_t)width > used) ? (size_t)width - used : 0;
+    size_t lpad = 0, mpad = 0, rpad = 0;
+    if (align == '<') rpad = pad;
+    else if (align == '^') { lpad = pad / 2; rpad = pad - lpad; }
+    else if (align == '=') mpad = pad;
+    else lpad = pad;
+    char* r = (char*)_tr_checked_alloc(used + pad + 1); char* w = r;
+    memset(w, fill, lpad); w += lpad;
+    memcpy(w, sg, sl); w += sl; memcpy(w, pfx, pl); w += pl;
+    memset(w, fill, mpad); w += mpad;
+    memcpy(w, sv, blen); w += blen;
+    memset(w, fill, rpad); w += rpad;
+    *w = '\0'; return r;
+}
 /* s.chars() -> List[str] of single-character strings. */
 static inline List_TrStr* _tr_str_chars(const char* s) {
     List_TrStr* l = List_TrStr_new(); if (!s) return l;
@@ -4546,7 +5956,7 @@ static inline void StringBuilder_append_int(StringBuilder* sb, long long n) {
     StringBuilder_append(sb, tmp);
 }
 static inline void StringBuilder_append_float(StringBuilder* sb, double f) {
-    char tmp[32]; snprintf(tmp, sizeof(tmp), "%g", f);
+    char tmp[40]; _tr_float_repr_into(f, 0, tmp, sizeof tmp);
     StringBuilder_append(sb, tmp);
 }
 static inline long long StringBuilder_length(StringBuilder* sb) { return sb->buf->len; }
@@ -4611,8 +6021,46 @@ static inline char* _tr_c_strdup(char* s) {
 
 /* Use compiler builtins (no <math.h>) so these work at every tier. */
 static inline double _tr_get_inf(void) { return __builtin_inf(); }
+static inline double _tr_get_nan(void) { return __builtin_nan(""); }
 static inline bool   _tr_is_inf(double x) { return __builtin_isinf(x) != 0; }
 static inline bool   _tr_is_nan(double x) { return __builtin_isnan(x) != 0; }
+/* True for -0.0 and other negatives: the sign a `== 0.0` test cannot see. */
+static inline bool   _tr_float_signbit(double x) { return __builtin_signbit(x) != 0; }
+
+#ifndef TAURARO_BARE
+/* std.cmath: complex function `op` of re+im*i (C99 principal branches). The
+ * result's real part is returned by _tr_cmath_re, its imaginary part by
+ * _tr_cmath_im; ops 15/16 (abs, phase) are real, with an imaginary part of 0.
+ * GNU __real__/__imag__ and the builtins keep <complex.h>'s `I`/`complex`
+ * macros out of generated code. */
+static inline _Complex double _tr_cmath_apply(long long op, double re, double im) {
+    _Complex double z;
+    __real__ z = re;
+    __imag__ z = im;
+    switch (op) {
+        case 0:  return __builtin_csqrt(z);
+        case 1:  return __builtin_cexp(z);
+        case 2:  return __builtin_clog(z);
+        case 3:  return __builtin_csin(z);
+        case 4:  return __builtin_ccos(z);
+        case 5:  return __builtin_ctan(z);
+        case 6:  return __builtin_casin(z);
+        case 7:  return __builtin_cacos(z);
+        case 8:  return __builtin_catan(z);
+        case 9:  return __builtin_csinh(z);
+        case 10: return __builtin_ccosh(z);
+        case 11: return __builtin_ctanh(z);
+        case 12: return __builtin_casinh(z);
+        case 13: return __builtin_cacosh(z);
+        case 14: return __builtin_catanh(z);
+        case 15: return __builtin_cabs(z);
+        case 16: return __builtin_carg(z);
+    }
+    return z;
+}
+static inline double _tr_cmath_re(long long op, double re, double im) { return __real__ _tr_cmath_apply(op, re, im); }
+static inline double _tr_cmath_im(long long op, double re, double im) { return __imag__ _tr_cmath_apply(op, re, im); }
+#endif
 
 /* ── MMIO intrinsics for bare-metal device drivers (std/hal/mmio.tr) ──────
  * Volatile so the compiler never elides or reorders a hardware register access.
@@ -4661,6 +6109,12 @@ static inline char* _tr_strftime(long long ts, const char* fmt) {
     char* buf=(char*)_tr_c_malloc(256); if(!buf) return _tr_empty_heap_str();
     strftime(buf,256,fmt,m); return buf;
 }
+/* Wall-clock microseconds since the Unix epoch (C11 timespec_get). */
+static inline long long _tr_timestamp_us(void) {
+    struct timespec ts;
+    if (timespec_get(&ts, TIME_UTC) != TIME_UTC) return (long long)time(NULL) * 1000000LL;
+    return (long long)ts.tv_sec * 1000000LL + (long long)ts.tv_nsec / 1000LL;
+}
 #else  /* no <time.h> (bare toolchain): no calendar/RTC — stub the datetime helpers */
 static inline int    _tr_tm_year(long long ts)    { (void)ts; return 1970; }
 static inline int    _tr_tm_month(long long ts)   { (void)ts; return 1; }
@@ -4676,6 +6130,7 @@ static inline long long _tr_tm_make(int year,int month,int day,int hour,int mi,i
 static inline char* _tr_strftime(long long ts, const char* fmt) {
     (void)ts;(void)fmt; return _tr_empty_heap_str();
 }
+static inline long long _tr_timestamp_us(void) { return 0LL; }
 #endif
 
 /* -- OS / System helpers (platform-specific) ------------------------------- */
@@ -5128,7 +6583,7 @@ static inline char* _tr_exe_dir(void) {
     if(!realpath(tmp,buf)){strcpy(buf,".");return buf;}
     for(int i=(int)strlen(buf)-1;i>0;i--){if(buf[i]=='/'){buf[i]='\0';break;}}
     return buf;
-#elif defined(__linux__)
+#elif defined(__linux__) && !defined(TAURARO_BARE)
     char* buf=(char*)_tr_c_malloc(4096);
     ssize_t n=readlink("/proc/self/exe",buf,4095);
     if(n<=0){buf[0]='.';buf[1]='\0';return buf;}
@@ -5410,56 +6865,438 @@ static inline char* _tr_uuid_v4(void) {
     return out;
 }
 
-/* ── MD5 (compact, for legacy use) ─────────────────────────────────────── */
-static inline char* _tr_md5_hex(char* s) {
-    /* Minimal MD5; message expanded inline. */
-    static const uint32_t T[64]={
-        0xd76aa478,0xe8c7b756,0x242070db,0xc1bdceee,0xf57c0faf,0x4787c62a,0xa8304613,0xfd469501,
-        0x698098d8,0x8b44f7af,0xffff5bb1,0x895cd7be,0x6b901122,0xfd987193,0xa679438e,0x49b40821,
-        0xf61e2562,0xc040b340,0x265e5a51,0xe9b6c7aa,0xd62f105d,0x02441453,0xd8a1e681,0xe7d3fbc8,
-        0x21e1cde6,0xc33707d6,0xf4d50d87,0x455a14ed,0xa9e3e905,0xfcefa3f8,0x676f02d9,0x8d2a4c8a,
-        0xfffa3942,0x8771f681,0x6d9d6122,0xfde5380c,0xa4beea44,0x4bdecfa9,0xf6bb4b60,0xbebfbc70,
-        0x289b7ec6,0xeaa127fa,0xd4ef3085,0x04881d05,0xd9d4d039,0xe6db99e5,0x1fa27cf8,0xc4ac5665,
-        0xf4292244,0x432aff97,0xab9423a7,0xfc93a039,0x655b59c3,0x8f0ccc92,0xffeff47d,0x85845dd1,
-        0x6fa87e4f,0xfe2ce6e0,0xa3014314,0x4e0811a1,0xf7537e82,0xbd3af235,0x2ad7d2bb,0xeb86d391
-    };
-    static const int S[64]={7,12,17,22,7,12,17,22,7,12,17,22,7,12,17,22,
-                             5, 9,14,20,5, 9,14,20,5, 9,14,20,5, 9,14,20,
-                             4,11,16,23,4,11,16,23,4,11,16,23,4,11,16,23,
-                             6,10,15,21,6,10,15,21,6,10,15,21,6,10,15,21};
-    size_t ilen = s ? strlen(s) : 0;
-    size_t padlen = ((ilen+8)/64+1)*64;
-    uint8_t* msg = (uint8_t*)TAURARO_CALLOC(1,padlen);
-    if(!msg) return _tr_strdup("00000000000000000000000000000000");
-    if(s) memcpy(msg,s,ilen);
-    msg[ilen]=0x80;
-    uint64_t bits=(uint64_t)ilen*8;
-    for(int i=0;i<8;i++) msg[padlen-8+i]=(uint8_t)(bits>>(uint64_t)(i*8));
-    uint32_t a=0x67452301,b=0xefcdab89,cc=0x98badcfe,d=0x10325476;
-    for(size_t off=0;off<padlen;off+=64){
-        uint32_t M[16],A=a,B=b,C=cc,D=d;
-        for(int i=0;i<16;i++) M[i]=((uint32_t)msg[off+i*4])|((uint32_t)msg[off+i*4+1]<<8)|((uint32_t)msg[off+i*4+2]<<16)|((uint32_t)msg[off+i*4+3]<<24);
-        for(int i=0;i<64;i++){
-            uint32_t F,g2;
-            if(i<16){F=(_TR_CH(B,C,D));g2=(uint32_t)i;}
-            else if(i<32){F=(D^(B&(C^D)));g2=(uint32_t)(5*i+1)%16;}
-            else if(i<48){F=(B^C^D);g2=(uint32_t)(3*i+5)%16;}
-            else{F=(C^(B|(~D)));g2=(uint32_t)(7*i)%16;}
-            F=F+A+T[i]+M[g2];
-            A=D;D=C;C=B;B=B+((F<<S[i])|(F>>(32-S[i])));
-        }
-        a+=A;b+=B;cc+=C;d+=D;
+/* ── MD5 (legacy use; also backs hashlib-style "md5") ──────────────────── */
+static const uint32_t _tr_md5_T[64]={
+    0xd76aa478,0xe8c7b756,0x242070db,0xc1bdceee,0xf57c0faf,0x4787c62a,0xa8304613,0xfd469501,
+    0x698098d8,0x8b44f7af,0xffff5bb1,0x895cd7be,0x6b901122,0xfd987193,0xa679438e,0x49b40821,
+    0xf61e2562,0xc040b340,0x265e5a51,0xe9b6c7aa,0xd62f105d,0x02441453,0xd8a1e681,0xe7d3fbc8,
+    0x21e1cde6,0xc33707d6,0xf4d50d87,0x455a14ed,0xa9e3e905,0xfcefa3f8,0x676f02d9,0x8d2a4c8a,
+    0xfffa3942,0x8771f681,0x6d9d6122,0xfde5380c,0xa4beea44,0x4bdecfa9,0xf6bb4b60,0xbebfbc70,
+    0x289b7ec6,0xeaa127fa,0xd4ef3085,0x04881d05,0xd9d4d039,0xe6db99e5,0x1fa27cf8,0xc4ac5665,
+    0xf4292244,0x432aff97,0xab9423a7,0xfc93a039,0x655b59c3,0x8f0ccc92,0xffeff47d,0x85845dd1,
+    0x6fa87e4f,0xfe2ce6e0,0xa3014314,0x4e0811a1,0xf7537e82,0xbd3af235,0x2ad7d2bb,0xeb86d391
+};
+static const int _tr_md5_S[64]={7,12,17,22,7,12,17,22,7,12,17,22,7,12,17,22,
+                                5, 9,14,20,5, 9,14,20,5, 9,14,20,5, 9,14,20,
+                                4,11,16,23,4,11,16,23,4,11,16,23,4,11,16,23,
+                                6,10,15,21,6,10,15,21,6,10,15,21,6,10,15,21};
+typedef struct { uint32_t h[4]; uint8_t buf[64]; uint64_t bits; uint32_t buf_len; } _TrMD5Ctx;
+static inline void _tr_md5_init(_TrMD5Ctx* c) {
+    c->h[0]=0x67452301;c->h[1]=0xefcdab89;c->h[2]=0x98badcfe;c->h[3]=0x10325476;c->bits=0;c->buf_len=0;
+}
+static inline void _tr_md5_block(_TrMD5Ctx* c, const uint8_t* p) {
+    uint32_t M[16],A=c->h[0],B=c->h[1],C=c->h[2],D=c->h[3];
+    for(int i=0;i<16;i++) M[i]=((uint32_t)p[i*4])|((uint32_t)p[i*4+1]<<8)|((uint32_t)p[i*4+2]<<16)|((uint32_t)p[i*4+3]<<24);
+    for(int i=0;i<64;i++){
+        uint32_t F,g2;
+        if(i<16){F=(_TR_CH(B,C,D));g2=(uint32_t)i;}
+        else if(i<32){F=(C^(D&(B^C)));g2=(uint32_t)(5*i+1)%16;}
+        else if(i<48){F=(B^C^D);g2=(uint32_t)(3*i+5)%16;}
+        else{F=(C^(B|(~D)));g2=(uint32_t)(7*i)%16;}
+        F=F+A+_tr_md5_T[i]+M[g2];
+        A=D;D=C;C=B;B=B+((F<<_tr_md5_S[i])|(F>>(32-_tr_md5_S[i])));
     }
-    TAURARO_FREE(msg);
-    char* out=(char*)TAURARO_ALLOC(33); if(!out) return _tr_strdup("00000000000000000000000000000000");
-    uint32_t r[4]={a,b,cc,d};
-    for(int i=0;i<4;i++) for(int j=0;j<4;j++){
-        uint8_t byte=(uint8_t)(r[i]>>(j*8));
-        out[i*8+j*2]=_tr_hex_lc[byte>>4]; out[i*8+j*2+1]=_tr_hex_lc[byte&15];
+    c->h[0]+=A;c->h[1]+=B;c->h[2]+=C;c->h[3]+=D;
+}
+static inline void _tr_md5_update(_TrMD5Ctx* c, const uint8_t* data, size_t len) {
+    for(size_t i=0;i<len;i++){
+        c->buf[c->buf_len++]=data[i]; c->bits+=8;
+        if(c->buf_len==64){_tr_md5_block(c,c->buf);c->buf_len=0;}
     }
+}
+static inline void _tr_md5_final(_TrMD5Ctx* c, uint8_t* dig) {
+    uint64_t bits=c->bits;
+    c->buf[c->buf_len++]=0x80;
+    while(c->buf_len!=56){if(c->buf_len==64){_tr_md5_block(c,c->buf);c->buf_len=0;}c->buf[c->buf_len++]=0;}
+    for(int i=0;i<8;i++) c->buf[56+i]=(uint8_t)(bits>>(i*8));
+    _tr_md5_block(c,c->buf);
+    for(int i=0;i<4;i++) for(int j=0;j<4;j++) dig[i*4+j]=(uint8_t)(c->h[i]>>(j*8));
+}
+static inline char* _tr_md5_hex(char* s) {
+    _TrMD5Ctx ctx; uint8_t dig[16];
+    _tr_md5_init(&ctx);
+    if(s) _tr_md5_update(&ctx,(const uint8_t*)s,strlen(s));
+    _tr_md5_final(&ctx,dig);
+    char* out=(char*)TAURARO_ALLOC(33); if(!out) return _tr_strdup("00000000000000000000000000000000");
+    for(int i=0;i<16;i++){out[i*2]=_tr_hex_lc[dig[i]>>4];out[i*2+1]=_tr_hex_lc[dig[i]&15];}
     out[32]='\0'; return out;
 }
 
+/* ── SHA-512 / SHA-384 / SHA-224 ───────────────────────────────────────── */
+static const uint64_t _tr_sha512_K[80] = {
+    0x428a2f98d728ae22ULL,0x7137449123ef65cdULL,0xb5c0fbcfec4d3b2fULL,0xe9b5dba58189dbbcULL,
+    0x3956c25bf348b538ULL,0x59f111f1b605d019ULL,0x923f82a4af194f9bULL,0xab1c5ed5da6d8118ULL,
+    0xd807aa98a3030242ULL,0x12835b0145706fbeULL,0x243185be4ee4b28cULL,0x550c7dc3d5ffb4e2ULL,
+    0x72be5d74f27b896fULL,0x80deb1fe3b1696b1ULL,0x9bdc06a725c71235ULL,0xc19bf174cf692694ULL,
+    0xe49b69c19ef14ad2ULL,0xefbe4786384f25e3ULL,0x0fc19dc68b8cd5b5ULL,0x240ca1cc77ac9c65ULL,
+    0x2de92c6f592b0275ULL,0x4a7484aa6ea6e483ULL,0x5cb0a9dcbd41fbd4ULL,0x76f988da831153b5ULL,
+    0x983e5152ee66dfabULL,0xa831c66d2db43210ULL,0xb00327c898fb213fULL,0xbf597fc7beef0ee4ULL,
+    0xc6e00bf33da88fc2ULL,0xd5a79147930aa725ULL,0x06ca6351e003826fULL,0x142929670a0e6e70ULL,
+    0x27b70a8546d22ffcULL,0x2e1b21385c26c926ULL,0x4d2c6dfc5ac42aedULL,0x53380d139d95b3dfULL,
+    0x650a73548baf63deULL,0x766a0abb3c77b2a8ULL,0x81c2c92e47edaee6ULL,0x92722c851482353bULL,
+    0xa2bfe8a14cf10364ULL,0xa81a664bbc423001ULL,0xc24b8b70d0f89791ULL,0xc76c51a30654be30ULL,
+    0xd192e819d6ef5218ULL,0xd69906245565a910ULL,0xf40e35855771202aULL,0x106aa07032bbd1b8ULL,
+    0x19a4c116b8d2d0c8ULL,0x1e376c085141ab53ULL,0x2748774cdf8eeb99ULL,0x34b0bcb5e19b48a8ULL,
+    0x391c0cb3c5c95a63ULL,0x4ed8aa4ae3418acbULL,0x5b9cca4f7763e373ULL,0x682e6ff3d6b2b8a3ULL,
+    0x748f82ee5defb2fcULL,0x78a5636f43172f60ULL,0x84c87814a1f0ab72ULL,0x8cc702081a6439ecULL,
+    0x90befffa23631e28ULL,0xa4506cebde82bde9ULL,0xbef9a3f7b2c67915ULL,0xc67178f2e372532bULL,
+    0xca273eceea26619cULL,0xd186b8c721c0c207ULL,0xeada7dd6cde0eb1eULL,0xf57d4f7fee6ed178ULL,
+    0x06f067aa72176fbaULL,0x0a637dc5a2c898a6ULL,0x113f9804bef90daeULL,0x1b710b35131c471bULL,
+    0x28db77f523047d84ULL,0x32caab7b40c72493ULL,0x3c9ebe0a15c9bebcULL,0x431d67c49c100d4cULL,
+    0x4cc5d4becb3e42b6ULL,0x597f299cfc657e2aULL,0x5fcb6fab3ad6faecULL,0x6c44198c4a475817ULL
+};
+typedef struct { uint64_t h[8]; uint8_t buf[128]; uint64_t bits; uint32_t buf_len; } _TrSHA512Ctx;
+static inline void _tr_sha512_init(_TrSHA512Ctx* c, int is384) {
+    static const uint64_t iv512[8]={0x6a09e667f3bcc908ULL,0xbb67ae8584caa73bULL,0x3c6ef372fe94f82bULL,0xa54ff53a5f1d36f1ULL,0x510e527fade682d1ULL,0x9b05688c2b3e6c1fULL,0x1f83d9abfb41bd6bULL,0x5be0cd19137e2179ULL};
+    static const uint64_t iv384[8]={0xcbbb9d5dc1059ed8ULL,0x629a292a367cd507ULL,0x9159015a3070dd17ULL,0x152fecd8f70e5939ULL,0x67332667ffc00b31ULL,0x8eb44a8768581511ULL,0xdb0c2e0d64f98fa7ULL,0x47b5481dbefa4fa4ULL};
+    memcpy(c->h,is384?iv384:iv512,sizeof c->h); c->bits=0; c->buf_len=0;
+}
+#define _TR_R64(x,n) (((x)>>(n))|((x)<<(64-(n))))
+static inline void _tr_sha512_block(_TrSHA512Ctx* c, const uint8_t* blk) {
+    uint64_t w[80],a,b,cc,d,e,f,g,h,t1,t2;
+    for(int i=0;i<16;i++){w[i]=0;for(int j=0;j<8;j++)w[i]=(w[i]<<8)|blk[i*8+j];}
+    for(int i=16;i<80;i++){
+        uint64_t s0=_TR_R64(w[i-15],1)^_TR_R64(w[i-15],8)^(w[i-15]>>7);
+        uint64_t s1=_TR_R64(w[i-2],19)^_TR_R64(w[i-2],61)^(w[i-2]>>6);
+        w[i]=w[i-16]+s0+w[i-7]+s1;
+    }
+    a=c->h[0];b=c->h[1];cc=c->h[2];d=c->h[3];e=c->h[4];f=c->h[5];g=c->h[6];h=c->h[7];
+    for(int i=0;i<80;i++){
+        t1=h+(_TR_R64(e,14)^_TR_R64(e,18)^_TR_R64(e,41))+((e&f)^(~e&g))+_tr_sha512_K[i]+w[i];
+        t2=(_TR_R64(a,28)^_TR_R64(a,34)^_TR_R64(a,39))+((a&b)^(a&cc)^(b&cc));
+        h=g;g=f;f=e;e=d+t1;d=cc;cc=b;b=a;a=t1+t2;
+    }
+    c->h[0]+=a;c->h[1]+=b;c->h[2]+=cc;c->h[3]+=d;c->h[4]+=e;c->h[5]+=f;c->h[6]+=g;c->h[7]+=h;
+}
+static inline void _tr_sha512_update(_TrSHA512Ctx* c, const uint8_t* data, size_t len) {
+    for(size_t i=0;i<len;i++){
+        c->buf[c->buf_len++]=data[i]; c->bits+=8;
+        if(c->buf_len==128){_tr_sha512_block(c,c->buf);c->buf_len=0;}
+    }
+}
+static inline void _tr_sha512_final(_TrSHA512Ctx* c, uint8_t* dig) {
+    uint64_t bits=c->bits;
+    c->buf[c->buf_len++]=0x80;
+    while(c->buf_len!=112){if(c->buf_len==128){_tr_sha512_block(c,c->buf);c->buf_len=0;}c->buf[c->buf_len++]=0;}
+    for(int i=0;i<8;i++) c->buf[112+i]=0;
+    for(int i=0;i<8;i++) c->buf[120+i]=(uint8_t)(bits>>(56-i*8));
+    _tr_sha512_block(c,c->buf);
+    for(int i=0;i<8;i++) for(int j=0;j<8;j++) dig[i*8+j]=(uint8_t)(c->h[i]>>(56-j*8));
+}
+static inline void _tr_sha224_init(_TrSHA256Ctx* c) {
+    static const uint32_t iv[8]={0xc1059ed8,0x367cd507,0x3070dd17,0xf70e5939,0xffc00b31,0x68581511,0x64f98fa7,0xbefa4fa4};
+    memcpy(c->h,iv,sizeof c->h); c->bits=0; c->buf_len=0;
+}
+
+/* ── SHA-3 (Keccak-f[1600]) ────────────────────────────────────────────── */
+static const uint64_t _tr_keccak_RC[24] = {
+    0x0000000000000001ULL,0x0000000000008082ULL,0x800000000000808aULL,0x8000000080008000ULL,
+    0x000000000000808bULL,0x0000000080000001ULL,0x8000000080008081ULL,0x8000000000008009ULL,
+    0x000000000000008aULL,0x0000000000000088ULL,0x0000000080008009ULL,0x000000008000000aULL,
+    0x000000008000808bULL,0x800000000000008bULL,0x8000000000008089ULL,0x8000000000008003ULL,
+    0x8000000000008002ULL,0x8000000000000080ULL,0x000000000000800aULL,0x800000008000000aULL,
+    0x8000000080008081ULL,0x8000000000008080ULL,0x0000000080000001ULL,0x8000000080008008ULL
+};
+static const int _tr_keccak_rotc[24]={1,3,6,10,15,21,28,36,45,55,2,14,27,41,56,8,25,43,62,18,39,61,20,44};
+static const int _tr_keccak_piln[24]={10,7,11,17,18,3,5,16,8,21,24,4,15,23,19,13,12,2,20,14,22,9,6,1};
+static inline void _tr_keccakf(uint64_t st[25]) {
+    uint64_t bc[5],t;
+    for(int r=0;r<24;r++){
+        for(int i=0;i<5;i++) bc[i]=st[i]^st[i+5]^st[i+10]^st[i+15]^st[i+20];
+        for(int i=0;i<5;i++){ t=bc[(i+4)%5]^((bc[(i+1)%5]<<1)|(bc[(i+1)%5]>>63)); for(int j=0;j<25;j+=5) st[j+i]^=t; }
+        t=st[1];
+        for(int i=0;i<24;i++){ int j=_tr_keccak_piln[i]; bc[0]=st[j]; st[j]=(t<<_tr_keccak_rotc[i])|(t>>(64-_tr_keccak_rotc[i])); t=bc[0]; }
+        for(int j=0;j<25;j+=5){ for(int i=0;i<5;i++) bc[i]=st[j+i]; for(int i=0;i<5;i++) st[j+i]^=(~bc[(i+1)%5])&bc[(i+2)%5]; }
+        st[0]^=_tr_keccak_RC[r];
+    }
+}
+typedef struct { uint64_t st[25]; uint32_t rate, pos, dlen; } _TrSHA3Ctx;
+static inline void _tr_sha3_init(_TrSHA3Ctx* c, int dlen) {
+    memset(c->st,0,sizeof c->st); c->dlen=(uint32_t)dlen; c->rate=200-2*(uint32_t)dlen; c->pos=0;
+}
+static inline void _tr_sha3_absorb(_TrSHA3Ctx* c, uint8_t b) {
+    c->st[c->pos/8]^=(uint64_t)b<<(8*(c->pos%8));
+    if(++c->pos==c->rate){_tr_keccakf(c->st);c->pos=0;}
+}
+static inline void _tr_sha3_update(_TrSHA3Ctx* c, const uint8_t* data, size_t len) {
+    for(size_t i=0;i<len;i++) _tr_sha3_absorb(c,data[i]);
+}
+static inline void _tr_sha3_final(_TrSHA3Ctx* c, uint8_t* dig) {
+    c->st[c->pos/8]^=(uint64_t)0x06<<(8*(c->pos%8));
+    c->st[(c->rate-1)/8]^=(uint64_t)0x80<<(8*((c->rate-1)%8));
+    _tr_keccakf(c->st);
+    for(uint32_t i=0;i<c->dlen;i++) dig[i]=(uint8_t)(c->st[i/8]>>(8*(i%8)));
+}
+
+/* ── BLAKE2b / BLAKE2s (unkeyed, full-length digest) ───────────────────── */
+static const uint8_t _tr_blake2_sigma[10][16] = {
+    {0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15},{14,10,4,8,9,15,13,6,1,12,0,2,11,7,5,3},
+    {11,8,12,0,5,2,15,13,10,14,3,6,7,1,9,4},{7,9,3,1,13,12,11,14,2,6,5,10,4,0,15,8},
+    {9,0,5,7,2,4,10,15,14,1,11,12,6,8,3,13},{2,12,6,10,0,11,8,3,4,13,7,5,15,14,1,9},
+    {12,5,1,15,14,13,4,10,0,7,6,3,9,2,8,11},{13,11,7,14,12,1,3,9,5,0,15,4,8,6,2,10},
+    {6,15,14,9,11,3,0,8,12,2,13,7,1,4,10,5},{10,2,8,4,7,6,1,5,15,11,9,14,3,12,13,0}
+};
+typedef struct { uint64_t h[8], t; uint8_t buf[128]; uint32_t buf_len; } _TrBlake2bCtx;
+typedef struct { uint32_t h[8], t; uint8_t buf[64]; uint32_t buf_len; } _TrBlake2sCtx;
+static inline void _tr_blake2b_init(_TrBlake2bCtx* c) {
+    static const uint64_t iv[8]={0x6a09e667f3bcc908ULL,0xbb67ae8584caa73bULL,0x3c6ef372fe94f82bULL,0xa54ff53a5f1d36f1ULL,0x510e527fade682d1ULL,0x9b05688c2b3e6c1fULL,0x1f83d9abfb41bd6bULL,0x5be0cd19137e2179ULL};
+    memcpy(c->h,iv,sizeof c->h); c->h[0]^=0x01010000ULL^64; c->t=0; c->buf_len=0;
+}
+static inline void _tr_blake2b_compress(_TrBlake2bCtx* c, int last) {
+    static const uint64_t iv[8]={0x6a09e667f3bcc908ULL,0xbb67ae8584caa73bULL,0x3c6ef372fe94f82bULL,0xa54ff53a5f1d36f1ULL,0x510e527fade682d1ULL,0x9b05688c2b3e6c1fULL,0x1f83d9abfb41bd6bULL,0x5be0cd19137e2179ULL};
+    uint64_t v[16],m[16];
+    for(int i=0;i<8;i++){v[i]=c->h[i];v[i+8]=iv[i];}
+    v[12]^=c->t; if(last) v[14]=~v[14];
+    for(int i=0;i<16;i++){m[i]=0;for(int j=7;j>=0;j--)m[i]=(m[i]<<8)|c->buf[i*8+j];}
+#define _TR_B2B_G(a,b,cc,d,x,y) do{ v[a]=v[a]+v[b]+(x); v[d]=_TR_R64(v[d]^v[a],32); v[cc]=v[cc]+v[d]; v[b]=_TR_R64(v[b]^v[cc],24); \
+    v[a]=v[a]+v[b]+(y); v[d]=_TR_R64(v[d]^v[a],16); v[cc]=v[cc]+v[d]; v[b]=_TR_R64(v[b]^v[cc],63); }while(0)
+    for(int r=0;r<12;r++){
+        const uint8_t* s=_tr_blake2_sigma[r%10];
+        _TR_B2B_G(0,4,8,12,m[s[0]],m[s[1]]); _TR_B2B_G(1,5,9,13,m[s[2]],m[s[3]]);
+        _TR_B2B_G(2,6,10,14,m[s[4]],m[s[5]]); _TR_B2B_G(3,7,11,15,m[s[6]],m[s[7]]);
+        _TR_B2B_G(0,5,10,15,m[s[8]],m[s[9]]); _TR_B2B_G(1,6,11,12,m[s[10]],m[s[11]]);
+        _TR_B2B_G(2,7,8,13,m[s[12]],m[s[13]]); _TR_B2B_G(3,4,9,14,m[s[14]],m[s[15]]);
+    }
+#undef _TR_B2B_G
+    for(int i=0;i<8;i++) c->h[i]^=v[i]^v[i+8];
+}
+static inline void _tr_blake2b_update(_TrBlake2bCtx* c, const uint8_t* data, size_t len) {
+    for(size_t i=0;i<len;i++){
+        /* the final block must go through compress(last=1), so only flush once more input arrives */
+        if(c->buf_len==128){c->t+=128;_tr_blake2b_compress(c,0);c->buf_len=0;}
+        c->buf[c->buf_len++]=data[i];
+    }
+}
+static inline void _tr_blake2b_final(_TrBlake2bCtx* c, uint8_t* dig) {
+    c->t+=c->buf_len;
+    while(c->buf_len<128) c->buf[c->buf_len++]=0;
+    _tr_blake2b_compress(c,1);
+    for(int i=0;i<64;i++) dig[i]=(uint8_t)(c->h[i/8]>>(8*(i%8)));
+}
+#define _TR_R32(x,n) (((x)>>(n))|((x)<<(32-(n))))
+static const uint32_t _tr_blake2s_IV[8]={0x6a09e667,0xbb67ae85,0x3c6ef372,0xa54ff53a,0x510e527f,0x9b05688c,0x1f83d9ab,0x5be0cd19};
+static inline void _tr_blake2s_init(_TrBlake2sCtx* c) {
+    memcpy(c->h,_tr_blake2s_IV,sizeof c->h); c->h[0]^=0x01010000u^32; c->t=0; c->buf_len=0;
+}
+static inline void _tr_blake2s_compress(_TrBlake2sCtx* c, int last) {
+    uint32_t v[16],m[16];
+    for(int i=0;i<8;i++){v[i]=c->h[i];v[i+8]=_tr_blake2s_IV[i];}
+    v[12]^=c->t; if(last) v[14]=~v[14];
+    for(int i=0;i<16;i++) m[i]=((uint32_t)c->buf[i*4])|((uint32_t)c->buf[i*4+1]<<8)|((uint32_t)c->buf[i*4+2]<<16)|((uint32_t)c->buf[i*4+3]<<24);
+#define _TR_B2S_G(a,b,cc,d,x,y) do{ v[a]=v[a]+v[b]+(x); v[d]=_TR_R32(v[d]^v[a],16); v[cc]=v[cc]+v[d]; v[b]=_TR_R32(v[b]^v[cc],12); \
+    v[a]=v[a]+v[b]+(y); v[d]=_TR_R32(v[d]^v[a],8); v[cc]=v[cc]+v[d]; v[b]=_TR_R32(v[b]^v[cc],7); }while(0)
+    for(int r=0;r<10;r++){
+        const uint8_t* s=_tr_blake2_sigma[r];
+        _TR_B2S_G(0,4,8,12,m[s[0]],m[s[1]]); _TR_B2S_G(1,5,9,13,m[s[2]],m[s[3]]);
+        _TR_B2S_G(2,6,10,14,m[s[4]],m[s[5]]); _TR_B2S_G(3,7,11,15,m[s[6]],m[s[7]]);
+        _TR_B2S_G(0,5,10,15,m[s[8]],m[s[9]]); _TR_B2S_G(1,6,11,12,m[s[10]],m[s[11]]);
+        _TR_B2S_G(2,7,8,13,m[s[12]],m[s[13]]); _TR_B2S_G(3,4,9,14,m[s[14]],m[s[15]]);
+    }
+#undef _TR_B2S_G
+    for(int i=0;i<8;i++) c->h[i]^=v[i]^v[i+8];
+}
+static inline void _tr_blake2s_update(_TrBlake2sCtx* c, const uint8_t* data, size_t len) {
+    for(size_t i=0;i<len;i++){
+        if(c->buf_len==64){c->t+=64;_tr_blake2s_compress(c,0);c->buf_len=0;}
+        c->buf[c->buf_len++]=data[i];
+    }
+}
+static inline void _tr_blake2s_final(_TrBlake2sCtx* c, uint8_t* dig) {
+    c->t+=c->buf_len;
+    while(c->buf_len<64) c->buf[c->buf_len++]=0;
+    _tr_blake2s_compress(c,1);
+    for(int i=0;i<32;i++) dig[i]=(uint8_t)(c->h[i/4]>>(8*(i%4)));
+}
+
+/* ── Named digests, HMAC and PBKDF2 (hashlib-compatible) ───────────────────
+ * Algorithms are looked up by name, case-insensitively, from the table below;
+ * an unknown name raises ValueError instead of quietly picking a default.
+ * Block sizes are the HMAC block sizes (the sponge rate for SHA-3). */
+enum { _TR_H_MD5, _TR_H_SHA1, _TR_H_SHA224, _TR_H_SHA256, _TR_H_SHA384, _TR_H_SHA512,
+       _TR_H_SHA3_224, _TR_H_SHA3_256, _TR_H_SHA3_384, _TR_H_SHA3_512, _TR_H_BLAKE2B, _TR_H_BLAKE2S, _TR_H_COUNT };
+static const struct { const char* name; int dsize; int bsize; } _tr_hash_algs[_TR_H_COUNT] = {
+    {"md5",16,64},{"sha1",20,64},{"sha224",28,64},{"sha256",32,64},{"sha384",48,128},{"sha512",64,128},
+    {"sha3_224",28,144},{"sha3_256",32,136},{"sha3_384",48,104},{"sha3_512",64,72},
+    {"blake2b",64,128},{"blake2s",32,64}
+};
+typedef struct {
+    int alg;
+    union { _TrMD5Ctx md5; _TrSHA1Ctx sha1; _TrSHA256Ctx sha256; _TrSHA512Ctx sha512;
+            _TrSHA3Ctx sha3; _TrBlake2bCtx b2b; _TrBlake2sCtx b2s; } u;
+} _TrHashCtx;
+static int _tr_hash_alg(const char* name) {
+    for(int a=0;name&&a<_TR_H_COUNT;a++){
+        const char* p=name; const char* q=_tr_hash_algs[a].name;
+        while(*p&&*q&&(*p>='A'&&*p<='Z'?*p+32:*p)==*q){p++;q++;}
+        if(!*p&&!*q) return a;
+    }
+    size_t n=(name?strlen(name):0)+48;
+    char* m=(char*)TAURARO_ALLOC(n);
+    snprintf(m,n,"ValueError: unsupported hash type %s",name?name:"");
+    _tr_exc_raise(m);
+    return -1;
+}
+static void _tr_hash_init(_TrHashCtx* c, int alg) {
+    c->alg=alg;
+    switch(alg){
+    case _TR_H_MD5: _tr_md5_init(&c->u.md5); break;
+    case _TR_H_SHA1: _tr_sha1_init(&c->u.sha1); break;
+    case _TR_H_SHA224: _tr_sha224_init(&c->u.sha256); break;
+    case _TR_H_SHA256: _tr_sha256_init(&c->u.sha256); break;
+    case _TR_H_SHA384: _tr_sha512_init(&c->u.sha512,1); break;
+    case _TR_H_SHA512: _tr_sha512_init(&c->u.sha512,0); break;
+    case _TR_H_BLAKE2B: _tr_blake2b_init(&c->u.b2b); break;
+    case _TR_H_BLAKE2S: _tr_blake2s_init(&c->u.b2s); break;
+    default: _tr_sha3_init(&c->u.sha3,_tr_hash_algs[alg].dsize); break;
+    }
+}
+static void _tr_hash_update(_TrHashCtx* c, const uint8_t* d, size_t n) {
+    switch(c->alg){
+    case _TR_H_MD5: _tr_md5_update(&c->u.md5,d,n); break;
+    case _TR_H_SHA1: _tr_sha1_update(&c->u.sha1,d,n); break;
+    case _TR_H_SHA224: case _TR_H_SHA256: _tr_sha256_update(&c->u.sha256,d,n); break;
+    case _TR_H_SHA384: case _TR_H_SHA512: _tr_sha512_update(&c->u.sha512,d,n); break;
+    case _TR_H_BLAKE2B: _tr_blake2b_update(&c->u.b2b,d,n); break;
+    case _TR_H_BLAKE2S: _tr_blake2s_update(&c->u.b2s,d,n); break;
+    default: _tr_sha3_update(&c->u.sha3,d,n); break;
+    }
+}
+/* Writes _tr_hash_algs[alg].dsize bytes to `out`. */
+static void _tr_hash_final(_TrHashCtx* c, uint8_t* out) {
+    uint8_t full[64];
+    switch(c->alg){
+    case _TR_H_MD5: _tr_md5_final(&c->u.md5,full); break;
+    case _TR_H_SHA1: _tr_sha1_final(&c->u.sha1,full); break;
+    case _TR_H_SHA224: case _TR_H_SHA256: _tr_sha256_final(&c->u.sha256,full); break;
+    case _TR_H_SHA384: case _TR_H_SHA512: _tr_sha512_final(&c->u.sha512,full); break;
+    case _TR_H_BLAKE2B: _tr_blake2b_final(&c->u.b2b,full); break;
+    case _TR_H_BLAKE2S: _tr_blake2s_final(&c->u.b2s,full); break;
+    default: _tr_sha3_final(&c->u.sha3,full); break;
+    }
+    memcpy(out,full,(size_t)_tr_hash_algs[c->alg].dsize);
+}
+static char* _tr_hex_of(const uint8_t* b, size_t n) {
+    char* out=(char*)TAURARO_ALLOC(n*2+1);
+    for(size_t i=0;i<n;i++){out[i*2]=_tr_hex_lc[b[i]>>4];out[i*2+1]=_tr_hex_lc[b[i]&15];}
+    out[n*2]='\0'; return out;
+}
+static inline char* _tr_hash_hex(char* name, char* s) {
+    int alg=_tr_hash_alg(name); _TrHashCtx c; uint8_t dig[64];
+    _tr_hash_init(&c,alg);
+    if(s) _tr_hash_update(&c,(const uint8_t*)s,strlen(s));
+    _tr_hash_final(&c,dig);
+    return _tr_hex_of(dig,(size_t)_tr_hash_algs[alg].dsize);
+}
+/* The same digests over a byte list, so NUL and high bytes hash as themselves:
+ * hashlib.new(name, data).digest() as a byte list, or .hexdigest(). */
+static void _tr_hash_list(char* name, List_i64* data, uint8_t* dig, int* dsize) {
+    int alg=_tr_hash_alg(name); _TrHashCtx c;
+    uint8_t* b=_tr_bytes_pack(data);
+    _tr_hash_init(&c,alg);
+    _tr_hash_update(&c,b,data?data->len:0);
+    _tr_hash_final(&c,dig);
+    free(b);
+    *dsize=_tr_hash_algs[alg].dsize;
+}
+static inline List_i64* _tr_hash_digest_bytes(char* name, List_i64* data) {
+    uint8_t dig[64]; int n;
+    _tr_hash_list(name,data,dig,&n);
+    return _tr_bytes_unpack(dig,(size_t)n);
+}
+static inline char* _tr_hash_hex_bytes(char* name, List_i64* data) {
+    uint8_t dig[64]; int n;
+    _tr_hash_list(name,data,dig,&n);
+    return _tr_hex_of(dig,(size_t)n);
+}
+/* Incremental hashing (hashlib's hash objects). The _TrHashCtx lives in the
+ * element buffer of a List[int] held by the Tauraro Hasher, so ARC frees it
+ * with the object and copy() is a plain list copy. update() absorbs only the
+ * new bytes; digest() finalises a copy of the state, so updates can go on. */
+#define _TR_HASH_WORDS ((sizeof(_TrHashCtx) + sizeof(long long) - 1) / sizeof(long long))
+static inline List_i64* _tr_hasher_new(char* name) {
+    int alg=_tr_hash_alg(name);
+    List_i64* st=List_i64_new();
+    for(size_t i=0;i<_TR_HASH_WORDS;i++) List_i64_append(st,0);
+    _tr_hash_init((_TrHashCtx*)st->data,alg);
+    return st;
+}
+static inline long long _tr_hash_digest_size(char* name) { return _tr_hash_algs[_tr_hash_alg(name)].dsize; }
+static inline long long _tr_hash_block_size(char* name) { return _tr_hash_algs[_tr_hash_alg(name)].bsize; }
+static inline void _tr_hasher_update(List_i64* st, List_i64* data) {
+    if(!data||data->len==0) return;
+    uint8_t* b=_tr_bytes_pack(data);
+    _tr_hash_update((_TrHashCtx*)st->data,b,data->len);
+    free(b);
+}
+static inline void _tr_hasher_update_str(List_i64* st, char* s) {
+    if(s) _tr_hash_update((_TrHashCtx*)st->data,(const uint8_t*)s,strlen(s));
+}
+static int _tr_hasher_final(List_i64* st, uint8_t* dig) {
+    _TrHashCtx c=*(_TrHashCtx*)st->data;
+    _tr_hash_final(&c,dig);
+    return _tr_hash_algs[c.alg].dsize;
+}
+static inline List_i64* _tr_hasher_digest(List_i64* st) {
+    uint8_t dig[64]; int n=_tr_hasher_final(st,dig);
+    return _tr_bytes_unpack(dig,(size_t)n);
+}
+static inline char* _tr_hasher_hexdigest(List_i64* st) {
+    uint8_t dig[64]; int n=_tr_hasher_final(st,dig);
+    return _tr_hex_of(dig,(size_t)n);
+}
+/* HMAC keyed state: the inner and outer contexts after absorbing the padded key,
+ * so each MAC (and each PBKDF2 round) only copies them instead of rehashing the key. */
+typedef struct { _TrHashCtx inner, outer; } _TrHmacCtx;
+static void _tr_hmac_key(_TrHmacCtx* h, int alg, const uint8_t* key, size_t klen) {
+    int bs=_tr_hash_algs[alg].bsize;
+    uint8_t k[144]={0}, pad[144];
+    if(klen>(size_t)bs){_TrHashCtx c;_tr_hash_init(&c,alg);_tr_hash_update(&c,key,klen);_tr_hash_final(&c,k);}
+    else if(klen) memcpy(k,key,klen);
+    for(int i=0;i<bs;i++) pad[i]=k[i]^0x36;
+    _tr_hash_init(&h->inner,alg); _tr_hash_update(&h->inner,pad,(size_t)bs);
+    for(int i=0;i<bs;i++) pad[i]=k[i]^0x5c;
+    _tr_hash_init(&h->outer,alg); _tr_hash_update(&h->outer,pad,(size_t)bs);
+}
+static void _tr_hmac_mac(const _TrHmacCtx* h, const uint8_t* msg, size_t n, uint8_t* out) {
+    _TrHashCtx c=h->inner; uint8_t in[64];
+    _tr_hash_update(&c,msg,n); _tr_hash_final(&c,in);
+    c=h->outer;
+    _tr_hash_update(&c,in,(size_t)_tr_hash_algs[c.alg].dsize); _tr_hash_final(&c,out);
+}
+static inline char* _tr_hmac_hex(char* name, char* key, char* msg) {
+    int alg=_tr_hash_alg(name); _TrHmacCtx h; uint8_t dig[64];
+    _tr_hmac_key(&h,alg,(const uint8_t*)key,key?strlen(key):0);
+    _tr_hmac_mac(&h,(const uint8_t*)msg,msg?strlen(msg):0,dig);
+    return _tr_hex_of(dig,(size_t)_tr_hash_algs[alg].dsize);
+}
+/* PBKDF2-HMAC (RFC 8018 §5.2). dklen <= 0 means the digest size, as in
+ * hashlib.pbkdf2_hmac(..., dklen=None). Returns the derived key as lowercase hex. */
+static inline char* _tr_pbkdf2_hmac_hex(char* name, char* pw, char* salt, long long iters, long long dklen) {
+    int alg=_tr_hash_alg(name);
+    if(iters<1) _tr_exc_raise((char*)"ValueError: iteration value must be greater than 0.");
+    if(dklen<0) _tr_exc_raise((char*)"ValueError: key length must be greater than 0.");
+    size_t hl=(size_t)_tr_hash_algs[alg].dsize, dk=dklen==0?hl:(size_t)dklen;
+    size_t sl=salt?strlen(salt):0;
+    _TrHmacCtx h; _tr_hmac_key(&h,alg,(const uint8_t*)pw,pw?strlen(pw):0);
+    uint8_t* out=(uint8_t*)TAURARO_ALLOC(dk);
+    uint8_t* sb=(uint8_t*)TAURARO_ALLOC(sl+4);
+    if(sl) memcpy(sb,salt,sl);
+    uint8_t u[64], t[64];
+    for(uint32_t blk=1, done=0; done<dk; blk++){
+        sb[sl]=(uint8_t)(blk>>24); sb[sl+1]=(uint8_t)(blk>>16); sb[sl+2]=(uint8_t)(blk>>8); sb[sl+3]=(uint8_t)blk;
+        _tr_hmac_mac(&h,sb,sl+4,u); memcpy(t,u,hl);
+        for(long long it=1;it<iters;it++){
+            _tr_hmac_mac(&h,u,hl,u);
+            for(size_t j=0;j<hl;j++) t[j]^=u[j];
+        }
+        size_t take=dk-done<hl?dk-done:hl;
+        memcpy(out+done,t,take); done+=(uint32_t)take;
+    }
+    char* hex=_tr_hex_of(out,dk);
+    TAURARO_FREE(sb); TAURARO_FREE(out);
+    return hex;
+}
+
 /* ═══════════════════════════════════════════════════════════════════════════
  * TLS/HTTPS — OpenSSL (opt-in: -DTAURARO_TLS_OPENSSL -lssl -lcrypto).
  * ═══════════════════════════════════════════════════════════════════════════ */
@@ -5472,6 +7309,7 @@ typedef struct { SSL_CTX* ctx; SSL* ssl; int fd; } _TrTLSConn;
 #  else
 #    define _TR_SOCK_CLOSE(fd) close(fd)
 #  endif
+static inline bool _tr_tls_available(void) { return true; }
 static inline char* _tr_tls_connect(char* host, int port) {
     static _Atomic int _tr_ssl_once = 0;
     if (atomic_fetch_add(&_tr_ssl_once,1)==0){SSL_library_init();SSL_load_error_strings();OpenSSL_add_all_algorithms();}
@@ -5527,6 +7365,7 @@ static inline char* _tr_tls_accept(char* ctxh, int fd) {
 }
 static inline void _tr_tls_server_free(char* ctxh) { if(ctxh) SSL_CTX_free((SSL_CTX*)ctxh); }
 #else
+static inline bool  _tr_tls_available(void)          { return false; }
 static inline char* _tr_tls_connect(char* h, int p) { (void)h;(void)p; return NULL; }
 static inline int   _tr_tls_send(char* h, char* d)  { (void)h;(void)d; return -1; }
 static inline char* _tr_tls_recv(char* h, int c)    { (void)h;(void)c; return _tr_strdup(""); }
@@ -5536,48 +7375,6 @@ static inline char* _tr_tls_accept(char* x, int fd) { (void)x;(void)fd; return N
 static inline void  _tr_tls_server_free(char* x) { (void)x; }
 #endif
 
-/* ═══════════════════════════════════════════════════════════════════════════
- * COMPRESS — zlib (opt-in: -DTAURARO_COMPRESS_ZLIB -lz).
- * ═══════════════════════════════════════════════════════════════════════════ */
-#ifdef TAURARO_COMPRESS_ZLIB
-#  include <zlib.h>
-static inline char* _tr_zlib_compress(char* input, int ilen, int* out_len) {
-    if(!input||ilen<=0){if(out_len)*out_len=0;return NULL;}
-    uLong bound=compressBound((uLong)ilen);
-    char* out=(char*)TAURARO_ALLOC(bound);if(!out){if(out_len)*out_len=0;return NULL;}
-    uLong dlen=bound;
-    if(compress2((Bytef*)out,&dlen,(const Bytef*)input,(uLong)ilen,Z_BEST_COMPRESSION)!=Z_OK){TAURARO_FREE(out);if(out_len)*out_len=0;return NULL;}
-    if(out_len)*out_len=(int)dlen; return out;
-}
-static inline char* _tr_zlib_decompress(char* input, int ilen, int max_out) {
-    if(!input||ilen<=0) return _tr_strdup("");
-    char* out=(char*)TAURARO_ALLOC((size_t)max_out+1);if(!out) return _tr_strdup("");
-    uLong dlen=(uLong)max_out;
-    if(uncompress((Bytef*)out,&dlen,(const Bytef*)input,(uLong)ilen)!=Z_OK){TAURARO_FREE(out);return _tr_strdup("");}
-    out[dlen]='\0'; return out;
-}
-static inline char* _tr_deflate(char* input, int ilen, int* out_len) {
-    if(!input||ilen<=0){if(out_len)*out_len=0;return NULL;}
-    z_stream s={0}; deflateInit2(&s,Z_BEST_COMPRESSION,Z_DEFLATED,-15,8,Z_DEFAULT_STRATEGY);
-    uLong bound=deflateBound(&s,(uLong)ilen);
-    char* out=(char*)TAURARO_ALLOC(bound);if(!out){deflateEnd(&s);if(out_len)*out_len=0;return NULL;}
-    s.next_in=(Bytef*)input;s.avail_in=(uInt)ilen;s.next_out=(Bytef*)out;s.avail_out=(uInt)bound;
-    deflate(&s,Z_FINISH);if(out_len)*out_len=(int)s.total_out;deflateEnd(&s);return out;
-}
-static inline char* _tr_inflate(char* input, int ilen, int max_out) {
-    if(!input||ilen<=0) return _tr_strdup("");
-    z_stream s={0};inflateInit2(&s,-15);
-    char* out=(char*)TAURARO_ALLOC((size_t)max_out+1);if(!out){inflateEnd(&s);return _tr_strdup("");}
-    s.next_in=(Bytef*)input;s.avail_in=(uInt)ilen;s.next_out=(Bytef*)out;s.avail_out=(uInt)max_out;
-    inflate(&s,Z_FINISH);int n=(int)s.total_out;inflateEnd(&s);out[n]='\0';return out;
-}
-#else
-static inline char* _tr_zlib_compress(char* i, int il, int* n) { (void)i;(void)il;if(n)*n=0;return NULL; }
-static inline char* _tr_zlib_decompress(char* i, int il, int m) { (void)i;(void)il;(void)m;return _tr_strdup(""); }
-static inline char* _tr_deflate(char* i, int il, int* n) { (void)i;(void)il;if(n)*n=0;return NULL; }
-static inline char* _tr_inflate(char* i, int il, int m) { (void)i;(void)il;(void)m;return _tr_strdup(""); }
-#endif
-
 /* ═══════════════════════════════════════════════════════════════════════════
  * UNICODE / UTF-8 — pure C, no external dependencies.
  * ═══════════════════════════════════════════════════════════════════════════ */
@@ -5596,6 +7393,31 @@ static inline int _tr_utf8_encode_cp(uint32_t cp, char* buf) {
     if(cp<0x10000){buf[0]=(char)(0xe0|(cp>>12));buf[1]=(char)(0x80|((cp>>6)&0x3f));buf[2]=(char)(0x80|(cp&0x3f));return 3;}
     buf[0]=(char)(0xf0|(cp>>18));buf[1]=(char)(0x80|((cp>>12)&0x3f));buf[2]=(char)(0x80|((cp>>6)&0x3f));buf[3]=(char)(0x80|(cp&0x3f));return 4;
 }
+/* str.encode([encoding]) -> byte list. An ASCII or Latin-1 encode that meets a
+ * character it can't represent raises UnicodeEncodeError naming the whole run. */
+static List_i64* _tr_str_encode(char* s, char* enc) {
+    int codec = _tr_codec(enc);
+    if (!s) s = (char*)"";
+    if (codec == 0) return _tr_bytes_unpack((const uint8_t*)s, strlen(s));
+    uint32_t lim = codec == 1 ? 128 : 256;
+    List_i64* out = List_i64_new();
+    const char* p = s;
+    size_t pos = 0;
+    while (*p) {
+        uint32_t cp = _tr_utf8_next(&p);
+        if (cp < lim) { List_i64_append(out, (long long)cp); pos++; continue; }
+        size_t first = pos;
+        for (const char* q = p; *q && _tr_utf8_next(&q) >= lim; p = q) pos++;
+        List_i64_free(out);
+        char what[48];
+        if (pos > first) snprintf(what, sizeof what, "encode characters");
+        else if (cp <= 0xFF) snprintf(what, sizeof what, "encode character '\\x%02x'", cp);
+        else if (cp <= 0xFFFF) snprintf(what, sizeof what, "encode character '\\u%04x'", cp);
+        else snprintf(what, sizeof what, "encode character '\\U%08x'", cp);
+        _tr_codec_error("UnicodeEncodeError", codec, what, first, pos, codec == 1 ? "ordinal not in range(128)" : "ordinal not in range(256)");
+    }
+    return out;
+}
 static inline int _tr_utf8_len(char* s) {
     if(!s) return 0; int n=0; const char* p=s; while(*p){_tr_utf8_next(&p);n++;} return n;
 }
@@ -5665,6 +7487,79 @@ static inline char* _tr_utf8_to_lower(char* s) {
     while(*p){uint32_t cp=_tr_utf8_next(&p);int n=_tr_utf8_encode_cp((uint32_t)_tr_unicode_to_lower((int)cp),tmp);memcpy(q,tmp,(size_t)n);q+=n;}
     *q='\0'; return out;
 }
+/* str.maketrans(frm, to[, delete]) -> Dict[int, int]: the i-th codepoint of frm
+ * maps to the i-th codepoint of to; codepoints in delete map to -1 (Python's
+ * None), which translate() drops. frm and to must have the same length. */
+static inline TrIDict* _tr_str_maketrans(const char* frm, const char* to, const char* del) {
+    if (_tr_utf8_len((char*)(frm ? frm : "")) != _tr_utf8_len((char*)(to ? to : "")))
+        _tr_exc_raise((char*)"ValueError: the first two maketrans arguments must have equal length");
+    TrIDict* d = _tr_idict_new(16);
+    const char* a = frm ? frm : ""; const char* b = to ? to : "";
+    while (*a && *b) { long long k = (long long)_tr_utf8_next(&a); _tr_idict_set(d, k, (long long)_tr_utf8_next(&b)); }
+    const char* c = del ? del : "";
+    while (*c) _tr_idict_set(d, (long long)_tr_utf8_next(&c), -1LL);
+    return d;
+}
+/* s.translate(table): replace each codepoint found in table by its mapped
+ * codepoint (negative = delete); unmapped codepoints are copied through. */
+static inline char* _tr_str_translate(const char* s, TrIDict* table) {
+    if (!s) return _tr_strdup("");
+    size_t cap = strlen(s) * 4 + 4; char* out = (char*)TAURARO_ALLOC(cap); char* q = out;
+    const char* p = s; char tmp[5];
+    while (*p) {
+        const char* at = p; uint32_t cp = _tr_utf8_next(&p);
+        _TrIDictNode* n = table ? table->buckets[(size_t)((unsigned long long)cp % table->cap)] : NULL;
+        while (n && n->key != (long long)cp) n = n->next;
+        if (!n) { memcpy(q, at, (size_t)(p - at)); q += p - at; continue; }
+        long long to = (long long)(intptr_t)n->value;
+        if (to < 0) continue;
+        int w = _tr_utf8_encode_cp((uint32_t)to, tmp); memcpy(q, tmp, (size_t)w); q += w;
+    }
+    *q = '\0'; return out;
+}
+/* Full case folding of one codepoint into out[], returning how many codepoints
+ * it folds to (1-3). Covers ASCII, Latin-1, Latin Extended-A, Greek, Cyrillic,
+ * the sharp s (both cases) and the Latin ligatures; anything else folds to
+ * itself. */
+static inline int _tr_unicode_casefold(uint32_t cp, uint32_t out[3]) {
+    uint32_t f = cp;
+    if (cp >= 'A' && cp <= 'Z') f = cp + 32;
+    else if (cp == 0xB5) f = 0x3BC;                                   /* micro sign -> mu */
+    else if (cp >= 0xC0 && cp <= 0xDE && cp != 0xD7) f = cp + 32;
+    else if (cp == 0xDF || cp == 0x1E9E) { out[0] = 's'; out[1] = 's'; return 2; }
+    else if (cp == 0x130) { out[0] = 'i'; out[1] = 0x307; return 2; }   /* I with dot above */
+    else if (cp == 0x149) { out[0] = 0x2BC; out[1] = 'n'; return 2; }
+    else if (cp == 0x178) f = 0xFF;
+    else if (cp == 0x17F) f = 's';                                    /* long s */
+    else if ((cp >= 0x100 && cp <= 0x12F) || (cp >= 0x132 && cp <= 0x137) || (cp >= 0x14A && cp <= 0x177)) f = cp | 1;
+    else if ((cp >= 0x139 && cp <= 0x148) || (cp >= 0x179 && cp <= 0x17E)) { if (cp & 1) f = cp + 1; }
+    else if (cp == 0x386) f = 0x3AC;
+    else if (cp >= 0x388 && cp <= 0x38A) f = cp + 37;
+    else if (cp == 0x38C) f = 0x3CC;
+    else if (cp == 0x38E || cp == 0x38F) f = cp + 63;
+    else if (cp >= 0x391 && cp <= 0x3AB && cp != 0x3A2) f = cp + 32;
+    else if (cp == 0x3C2) f = 0x3C3;                                  /* final sigma */
+    else if (cp >= 0x400 && cp <= 0x40F) f = cp + 80;
+    else if (cp >= 0x410 && cp <= 0x42F) f = cp + 32;
+    else if (cp >= 0xFB00 && cp <= 0xFB06) {
+        static const char* lig[7] = { "ff", "fi", "fl", "ffi", "ffl", "st", "st" };
+        const char* l = lig[cp - 0xFB00]; int n = 0;
+        while (l[n]) { out[n] = (uint32_t)l[n]; n++; }
+        return n;
+    }
+    out[0] = f; return 1;
+}
+/* s.casefold(): s with every codepoint case-folded, for caseless matching. */
+static inline char* _tr_str_casefold(const char* s) {
+    if (!s) return _tr_strdup("");
+    size_t cap = strlen(s) * 4 + 4; char* out = (char*)TAURARO_ALLOC(cap); char* q = out;
+    const char* p = s; char tmp[5]; uint32_t f[3];
+    while (*p) {
+        int n = _tr_unicode_casefold(_tr_utf8_next(&p), f);
+        for (int i = 0; i < n; i++) { int w = _tr_utf8_encode_cp(f[i], tmp); memcpy(q, tmp, (size_t)w); q += w; }
+    }
+    *q = '\0'; return out;
+}
 /* Return the codepoint category string: "L"=letter, "N"=digit, "Z"=space, "C"=other */
 static inline char* _tr_unicode_category(int cp) {
     if(_tr_unicode_is_letter(cp)) return _tr_strdup("L");
@@ -5731,7 +7626,7 @@ static void _tr_idict_clear(TrIDict* m) {
         while(n){ _TrIDictNode* nx=n->next; _tr_free(n); n=nx; }
         m->buckets[i]=NULL;
     }
-    m->len=0;
+    m->len=0; m->head=NULL; m->tail=NULL; m->mods++;
 }
 /* Free a Map[int,V]/Dict[int,V] (TrIDict) entirely - nodes, buckets array and
    the struct itself. Mirrors Dict_free() but for the int-keyed node layout
@@ -5863,7 +7758,7 @@ static void     _tr_iset_add(_TrISet* s, int64_t e)      { _tr_idict_set(s, e, (
 static int64_t  _tr_iset_contains(_TrISet* s, int64_t e) { return (int64_t)_tr_idict_contains(s, e); }
 static void     _tr_iset_remove(_TrISet* s, int64_t e)   { _tr_idict_remove(s, e); }
 static int64_t  _tr_iset_len(_TrISet* s)                 { return _tr_idict_len(s); }
-static void     _tr_iset_clear(_TrISet* s)               { if(s){ for(size_t i=0;i<s->cap;i++){ _TrIDictNode* n=s->buckets[i]; while(n){ _TrIDictNode* nx=n->next; free(n); n=nx; } s->buckets[i]=NULL; } s->len=0; } }
+static void     _tr_iset_clear(_TrISet* s)               { if(s){ for(size_t i=0;i<s->cap;i++){ _TrIDictNode* n=s->buckets[i]; while(n){ _TrIDictNode* nx=n->next; free(n); n=nx; } s->buckets[i]=NULL; } s->len=0; s->head=NULL; s->tail=NULL; s->mods++; } }
 static List_i64* _tr_iset_to_list(_TrISet* s)            { return _tr_idict_keys(s); }
 static _TrISet* _tr_iset_union(_TrISet* a, _TrISet* b) {
     _TrISet* r=_tr_iset_new(16);
@@ -5923,6 +7818,16 @@ static char* _tr_collection_to_str(const void* data, size_t len, size_t elem_siz
     buf = _tr_sb_append(buf, &blen, &cap, close);
     return buf;
 }
+/* A List_T / Set_T value rendered through its header, read once (the list
+ * expression may be a call or a fresh sorted() copy). The header is copied
+ * out with memcpy rather than read through a _TrListHdr*: the list was
+ * written as a List_T, and at -O2 strict aliasing lets the compiler move a
+ * _TrListHdr read above an inlined List_T_del_slice's store to len. */
+static char* _tr_list_hdr_to_str(const void* list, size_t elem_size, _TrElemFmt fmt, const char* open, const char* close, const char* sep) {
+    _TrListHdr h;
+    memcpy(&h, list, sizeof h);
+    return _tr_collection_to_str(h.data, h.len, elem_size, fmt, open, close, sep);
+}
 /* Build "{k1: v1, k2: v2}" from parallel key/value List headers. */
 static char* _tr_dict_to_str(const void* kdata, const void* vdata, size_t len, size_t vsize, _TrElemFmt kfmt, _TrElemFmt vfmt) {
     size_t cap, blen = 0;
@@ -6023,8 +7928,11 @@ static int _tr_cmp_str_asc (const void* a, const void* b) { return strcmp(*(char
 static int _tr_cmp_str_desc(const void* a, const void* b) { return strcmp(*(char**)b, *(char**)a); }
 static int _tr_cmp_i64_asc (const void* a, const void* b) { int64_t x=*(int64_t*)a,y=*(int64_t*)b; return (x>y)-(x<y); }
 static int _tr_cmp_i64_desc(const void* a, const void* b) { int64_t x=*(int64_t*)a,y=*(int64_t*)b; return (x<y)-(x>y); }
-static int _tr_cmp_f64_asc (const void* a, const void* b) { double x=*(double*)a,y=*(double*)b; return (x>y)-(x<y); }
-static int _tr_cmp_f64_desc(const void* a, const void* b) { double x=*(double*)a,y=*(double*)b; return (x<y)-(x>y); }
+/* NaN is unordered, which would make qsort's comparator inconsistent; treat it
+ * as greater than every number (and equal to other NaNs) so an ascending sort
+ * puts NaNs last and a descending one puts them first. */
+static int _tr_cmp_f64_asc (const void* a, const void* b) { double x=*(double*)a,y=*(double*)b; if(x!=x||y!=y) return (x!=x)-(y!=y); return (x>y)-(x<y); }
+static int _tr_cmp_f64_desc(const void* a, const void* b) { return _tr_cmp_f64_asc(b, a); }
 static void _tr_list_sort_str(List_str* l, int dir) { if(l&&l->len>1) qsort(l->data,(size_t)l->len,sizeof(char*),dir>0?_tr_cmp_str_asc:_tr_cmp_str_desc); }
 static int _tr_cmp_trstr_asc (const void* a, const void* b) { return strcmp(((const TrStr*)a)->data, ((const TrStr*)b)->data); }
 static int _tr_cmp_trstr_desc(const void* a, const void* b) { return strcmp(((const TrStr*)b)->data, ((const TrStr*)a)->data); }
@@ -6032,6 +7940,217 @@ static void _tr_list_sort_TrStr(List_TrStr* l, int dir) { if(l&&l->len>1) qsort(
 static void _tr_list_sort_i64(List_i64* l, int dir) { if(l&&l->len>1) qsort(l->data,(size_t)l->len,sizeof(int64_t),dir>0?_tr_cmp_i64_asc:_tr_cmp_i64_desc); }
 static void _tr_list_sort_f64(List_f64* l, int dir) { if(l&&l->len>1) qsort(l->data,(size_t)l->len,sizeof(double),dir>0?_tr_cmp_f64_asc:_tr_cmp_f64_desc); }
 static void _tr_list_sort_ptr(List_ptr* l, int dir) { (void)l; (void)dir; }
+/* Set equality (Python's: same elements, in any order) and the dict key of a
+ * FrozenSet[T]. The key is a canonical string, the elements sorted and
+ * joined, so equal sets give equal keys whatever their insertion order:
+ * "i:1,2,3" for an int set, "s:" then <len>:<bytes> per element for a str
+ * set (length-prefixed, so no element can fake a separator). */
+static int64_t _tr_iset_eq(_TrISet* a, _TrISet* b) {
+    if (_tr_iset_len(a) != _tr_iset_len(b)) return 0LL;
+    for (_TrIDictNode* n = a ? a->head : NULL; n; n = n->onext) if (!_tr_iset_contains(b, n->key)) return 0LL;
+    return 1LL;
+}
+static int64_t _tr_set_eq(_TrSet* a, _TrSet* b) {
+    if (_tr_set_len(a) != _tr_set_len(b)) return 0LL;
+    for (_DictNode* n = a ? a->head : NULL; n; n = n->onext) if (!_tr_set_contains(b, n->key)) return 0LL;
+    return 1LL;
+}
+static TrStr _tr_iset_key(_TrISet* s) {
+    int64_t n = _tr_iset_len(s), i = 0;
+    int64_t* v = (int64_t*)_tr_checked_alloc(sizeof(int64_t) * (size_t)(n > 0 ? n : 1));
+    for (_TrIDictNode* nd = s ? s->head : NULL; nd; nd = nd->onext) v[i++] = nd->key;
+    qsort(v, (size_t)n, sizeof(int64_t), _tr_cmp_i64_asc);
+    TrStr k = _tr_str_new(2 + (size_t)n * 21);
+    size_t len = 2;
+    memcpy(k.data, "i:", 2);
+    for (i = 0; i < n; i++) len += (size_t)sprintf(k.data + len, i ? ",%lld" : "%lld", (long long)v[i]);
+    k.data[len] = '\0';
+    _tr_free(v);
+    return k;
+}
+static TrStr _tr_set_key(_TrSet* s) {
+    int64_t n = _tr_set_len(s), i = 0;
+    char** v = (char**)_tr_checked_alloc(sizeof(char*) * (size_t)(n > 0 ? n : 1));
+    size_t len = 2;
+    for (_DictNode* nd = s ? s->head : NULL; nd; nd = nd->onext) { v[i++] = nd->key; len += strlen(nd->key) + 21; }
+    qsort(v, (size_t)n, sizeof(char*), _tr_cmp_str_asc);
+    TrStr k = _tr_str_new(len);
+    len = 2;
+    memcpy(k.data, "s:", 2);
+    for (i = 0; i < n; i++) {
+        size_t el = strlen(v[i]);
+        len += (size_t)sprintf(k.data + len, "%zu:", el);
+        memcpy(k.data + len, v[i], el);
+        len += el;
+    }
+    k.data[len] = '\0';
+    _tr_free(v);
+    return k;
+}
+/* The set a _tr_iset_key/_tr_set_key key encodes, rebuilt as a fresh set.
+ * Iterating a FrozenSet-keyed dict (or a set of FrozenSets) binds the loop
+ * variable to one, dropped through _tr_iset_unkey_drop/_tr_set_unkey_drop
+ * when the iteration ends. */
+static _TrISet* _tr_iset_unkey(const char* k) {
+    _TrISet* s = _tr_iset_new(8);
+    const char* p = k && k[0] && k[1] ? k + 2 : "";
+    while (*p) {
+        char* e;
+        _tr_iset_add(s, (int64_t)strtoll(p, &e, 10));
+        p = *e == ',' ? e + 1 : e;
+    }
+    return s;
+}
+static _TrSet* _tr_set_unkey(const char* k) {
+    _TrSet* s = _tr_set_new(8);
+    const char* p = k && k[0] && k[1] ? k + 2 : "";
+    while (*p) {
+        char* colon;
+        size_t el = (size_t)strtoull(p, &colon, 10);
+        char* e = (char*)_tr_checked_alloc(el + 1);
+        memcpy(e, colon + 1, el);
+        e[el] = '\0';
+        _tr_set_add(s, e);   /* the set keeps its own copy */
+        _tr_free(e);
+        p = colon + 1 + el;
+    }
+    return s;
+}
+static void _tr_iset_unkey_drop(_TrISet** s) { _tr_idict_free(*s); }
+static void _tr_set_unkey_drop(_TrSet** s)   { Dict_free(*s); }
+/* print()/str() of a FrozenSet stored as a key: frozenset({1, 2}). `kind` is
+ * 0 for int elements, 1 for float (stored as their f64 key bits), 2 for str. */
+static char* _tr_set_key_to_str(const char* k, int kind) {
+    if (kind == 2) {
+        _TrSet* s = _tr_set_unkey(k);
+        List_TrStr* l = _tr_set_to_list(s);
+        char* r = _tr_collection_to_str((void*)l->data, l->len, sizeof(TrStr), _tr_fmt_str, "frozenset({", "})", ", ");
+        List_TrStr_free(l);
+        Dict_free(s);
+        return r;
+    }
+    _TrISet* s = _tr_iset_unkey(k);
+    List_i64* l = _tr_iset_to_list(s);
+    char* r;
+    if (kind == 1) {
+        for (size_t i = 0; i < l->len; i++) { double d = _tr_key_f64(l->data[i]); memcpy(&l->data[i], &d, sizeof d); }
+        r = _tr_collection_to_str((void*)l->data, l->len, sizeof(double), _tr_fmt_f64, "frozenset({", "})", ", ");
+    } else {
+        r = _tr_collection_to_str((void*)l->data, l->len, sizeof(long long), _tr_fmt_i64, "frozenset({", "})", ", ");
+    }
+    List_i64_free(l);
+    _tr_idict_free(s);
+    return r;
+}
+/* keys() of a FrozenSet-keyed dict / set: each stored key decoded back to a set. */
+static List_ptr* _tr_dict_keys_iset(TrMap* d) {
+    List_ptr* out = List_ptr_new();
+    if (!d) return out;
+    for (_DictNode* n = d->head; n; n = n->onext) if (n->key && n->value) List_ptr_append(out, _tr_iset_unkey(n->key));
+    return out;
+}
+static List_ptr* _tr_dict_keys_sset(TrMap* d) {
+    List_ptr* out = List_ptr_new();
+    if (!d) return out;
+    for (_DictNode* n = d->head; n; n = n->onext) if (n->key && n->value) List_ptr_append(out, _tr_set_unkey(n->key));
+    return out;
+}
+static char* _tr_fmt_iset_key(const void* p) { return _tr_set_key_to_str(*(const char* const*)p, 0); }
+static char* _tr_fmt_fset_key(const void* p) { return _tr_set_key_to_str(*(const char* const*)p, 1); }
+static char* _tr_fmt_sset_key(const void* p) { return _tr_set_key_to_str(*(const char* const*)p, 2); }
+/* Canonical key of a tuple used as a Dict key or Set element. `sig` names each
+ * slot's kind: 'i' for an int/bool/char (or any other by-value slot), 'f' for a
+ * float (its -0.0 folded into 0.0), 's' for a boxed str. Number slots become 16
+ * hex digits and strs are length-prefixed, so two tuples share a key exactly
+ * when their elements are equal. _tr_tuple_unkey rebuilds the tuple. */
+static TrStr _tr_tuple_key(TrTuple t, const char* sig) {
+    size_t n = strlen(sig), len = 0, cap = 1;
+    for (size_t i = 0; i < n; i++) {
+        if (sig[i] == 's') { TrStr* b = (TrStr*)(uintptr_t)t.data[i]; cap += (b && b->data ? strlen(b->data) : 0) + 21; }
+        else cap += 16;
+    }
+    TrStr k = _tr_str_new(cap);
+    for (size_t i = 0; i < n; i++) {
+        if (sig[i] == 's') {
+            TrStr* b = (TrStr*)(uintptr_t)t.data[i];
+            const char* sv = b && b->data ? b->data : "";
+            size_t el = strlen(sv);
+            len += (size_t)sprintf(k.data + len, "%zu:", el);
+            memcpy(k.data + len, sv, el);
+            len += el;
+        } else {
+            long long v = t.data[i];
+            if (sig[i] == 'f') v = _tr_f64_key(_tr_ptr_to_f64((void*)(uintptr_t)v));
+            len += (size_t)sprintf(k.data + len, "%016llx", (unsigned long long)v);
+        }
+    }
+    k.data[len] = '\0';
+    return k;
+}
+static TrTuple _tr_tuple_unkey(const char* k, const char* sig) {
+    TrTuple t = {{0}};
+    for (size_t i = 0; sig[i] && k && *k; i++) {
+        if (sig[i] == 's') {
+            char* colon;
+            size_t el = (size_t)strtoull(k, &colon, 10);
+            TrStr sv = _tr_str_new(el);
+            memcpy(sv.data, colon + 1, el);
+            sv.data[el] = '\0';
+            t.data[i] = (long long)(uintptr_t)_tr_str_box(sv);
+            k = colon + 1 + el;
+        } else {
+            char hex[17];
+            memcpy(hex, k, 16);
+            hex[16] = '\0';
+            t.data[i] = (long long)strtoull(hex, NULL, 16);
+            k += 16;
+        }
+    }
+    return t;
+}
+/* keys() of a tuple-keyed dict / set: each stored key decoded back to a tuple. */
+static List_TrTuple* _tr_dict_keys_tuple(TrMap* d, const char* sig) {
+    List_TrTuple* out = List_TrTuple_new();
+    if (!d) return out;
+    for (_DictNode* n = d->head; n; n = n->onext)
+        if (n->key && n->value) List_TrTuple_append(out, _tr_tuple_unkey(n->key, sig));
+    return out;
+}
+/* sort(key) / sorted(xs, key): decorate-sort-undecorate.  The caller
+ * computes one key per element into keys[] (kind 0 = int64_t, 1 = double,
+ * 2 = TrStr); this merge-sorts an index permutation on those keys and then
+ * reorders data[] to match.  Merge sort keeps equal keys in their original
+ * order, and reverse (dir < 0) flips the comparison rather than the result,
+ * so it is stable too. */
+static int _tr_key_cmp(const void* keys, int kind, size_t a, size_t b) {
+    if (kind == 0) { int64_t x=((const int64_t*)keys)[a], y=((const int64_t*)keys)[b]; return (x>y)-(x<y); }
+    if (kind == 1) return _tr_cmp_f64_asc(&((const double*)keys)[a], &((const double*)keys)[b]);
+    return _tr_cmp_trstr_asc(&((const TrStr*)keys)[a], &((const TrStr*)keys)[b]);
+}
+static void _tr_sort_by_keys(void* data, size_t elem_size, size_t n, const void* keys, int kind, int dir) {
+    if (!data || n < 2) return;
+    size_t* idx = (size_t*)malloc(n * sizeof(size_t));
+    size_t* tmp = (size_t*)malloc(n * sizeof(size_t));
+    for (size_t i = 0; i < n; i++) idx[i] = i;
+    for (size_t w = 1; w < n; w *= 2) {
+        for (size_t lo = 0; lo < n; lo += 2 * w) {
+            size_t mid = lo + w < n ? lo + w : n, hi = lo + 2 * w < n ? lo + 2 * w : n;
+            size_t i = lo, j = mid, k = lo;
+            while (i < mid && j < hi) {
+                int c = _tr_key_cmp(keys, kind, idx[i], idx[j]);
+                if (dir < 0) c = -c;
+                tmp[k++] = c <= 0 ? idx[i++] : idx[j++];
+            }
+            while (i < mid) tmp[k++] = idx[i++];
+            while (j < hi)  tmp[k++] = idx[j++];
+        }
+        size_t* t = idx; idx = tmp; tmp = t;
+    }
+    char* out = (char*)malloc(n * elem_size);
+    for (size_t i = 0; i < n; i++) memcpy(out + i * elem_size, (char*)data + idx[i] * elem_size, elem_size);
+    memcpy(data, out, n * elem_size);
+    free(out); free(tmp); free(idx);
+}
 static int64_t _tr_list_sum_i64(List_i64* l) { int64_t s=0; if(l) for(int64_t i=0;i<(int64_t)l->len;i++) s+=l->data[i]; return s; }
 static double  _tr_list_sum_f64(List_f64* l) { double  s=0; if(l) for(int64_t i=0;i<(int64_t)l->len;i++) s+=l->data[i]; return s; }
 static int64_t _tr_list_min_i64(List_i64* l) { if(!l||l->len==0) return 0LL; int64_t m=l->data[0]; for(int64_t i=1;i<(int64_t)l->len;i++) if(l->data[i]<m) m=l->data[i]; return m; }
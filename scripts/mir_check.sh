@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+# MIR dump checks for tests/mir/.
+#
+# Every probe must lower under `--emit mir`. A probe may carry
+# `# EXPECT-MIR: text` lines; each text must appear in its dump. A `|` in the
+# text stands for a line break, so a line can pin a block's statements and
+# terminator in order.
+#
+# Run from the repo root:  bash scripts/mir_check.sh
+set -u
+ROOT="$(cd "$(dirname "$0")/.." && pwd)"
+cd "$ROOT"
+TAURAROC="${TAURAROC:-./tauraroc}"
+
+pass=0; fail=0
+for src in tests/mir/*.tr; do
+    name="$(basename "$src" .tr)"
+    out="$("$TAURAROC" "$src" --emit mir 2>&1)"
+    if [ $? -ne 0 ]; then echo "FAIL  $name (--emit mir failed)"; fail=$((fail+1)); continue; fi
+    ok=1
+    while IFS= read -r want; do
+        want="${want//|/$'\n'}"
+        case "$out" in
+            *"$want"*) ;;
+            *) echo "FAIL  $name (missing: ${want%%$'\n'*} ...)"; ok=0 ;;
+        esac
+    done < <(sed -n 's/^# EXPECT-MIR: //p' "$src")
+    if [ $ok -eq 1 ]; then echo "PASS  $name"; pass=$((pass+1)); else fail=$((fail+1)); fi
+done
+rm -rf build
+
+echo "mir: $pass passed, $fail failed"
+[ $fail -eq 0 ]
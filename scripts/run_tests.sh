@@ -4,7 +4,9 @@
 # Each test file uses std/test's TestRunner and is run with `tauraroc --run`.
 # A test file passes iff:
 #   - the compile+run exits 0, AND
-#   - stdout does NOT contain the word "FAILED"
+#   - stdout does NOT contain the word "FAILED" or an uncaught exception, AND
+#   - TestRunner's summary line ("<name>: all N tests passed.") was printed,
+#     so a program that stops early cannot pass
 #
 # Usage: scripts/run_tests.sh [path/to/single_test.tr]
 
@@ -37,7 +39,9 @@ for f in "${FILES[@]}"; do
     out=$("$TAURAROC" --run "$f" 2>&1)
     status=$?
     echo "$out"
-    if [ $status -ne 0 ] || echo "$out" | grep -q "FAILED"; then
+    if [ $status -ne 0 ] || echo "$out" | grep -q "FAILED" \
+       || echo "$out" | grep -qE "Unhandled exception|Aborted" \
+       || ! echo "$out" | grep -qE ': all [0-9]+ tests passed\.$'; then
         failed=$((failed + 1))
         failed_files+=("$f")
     fi
@@ -125,6 +129,388 @@ CEOF
     rm -rf "$libdir"
 fi
 
+# --- --lib embedding: tauraro_init() + exported globals ----------------------
+# A host never runs main(), so tauraro_init() runs the global initializers and
+# sets what std.sys.imports reports; an `export mut` global is declared in the
+# header for the host to set and read. The library must not define main() itself.
+if command -v cc >/dev/null 2>&1 || command -v gcc >/dev/null 2>&1; then
+    total=$((total + 1))
+    echo "==> --lib embedding"
+    CCBIN=$(command -v cc || command -v gcc)
+    embdir=$(mktemp -d)
+    cat > "$embdir/emb.tr" <<'TREOF'
+from std.sys.imports import Imports
+
+export mut rate: int = 3
+export mut total: int = 0
+mut calls: int = 10
+
+export def scaled(x: int) -> int:
+    calls = calls + 1
+    total = total + x * rate
+    return x * rate
+
+export def call_count() -> int:
+    return calls
+
+export def module_count() -> int:
+    return Imports.modules().len()
+TREOF
+    "$TAURAROC" "$embdir/emb.tr" -o "$embdir/emb" --lib >/dev/null 2>&1
+    cat > "$embdir/host.c" <<'CEOF'
+#include "emb.h"
+#include <stdio.h>
+int main(void){
+    tauraro_init(); tauraro_init();
+    long long a = scaled(2);
+    rate = 5;
+    long long b = scaled(2);
+    printf("%lld %lld %lld %lld %d\n", a, b, total, call_count(), module_count() > 1);
+    return 0;
+}
+CEOF
+    eout=""
+    if [ -f "$embdir/emb.so" ] && [[ "$(uname -s)" == "Linux" ]]; then
+        "$CCBIN" -std=gnu11 "$embdir/host.c" -I"$embdir" "$embdir/emb.so" -o "$embdir/host" >/dev/null 2>&1
+        [ -f "$embdir/host" ] && eout=$(cd "$embdir" && LD_LIBRARY_PATH="$embdir:${LD_LIBRARY_PATH:-}" ./host 2>/dev/null)
+        if command -v nm >/dev/null 2>&1 && nm -D --defined-only "$embdir/emb.so" | grep -qw main; then
+            eout="exports main"
+        fi
+    else
+        eout="6 10 16 12 1"   # only checked on Linux
+    fi
+    if [ "$eout" != "6 10 16 12 1" ]; then
+        echo "  FAILED (got: '$eout')"
+        failed=$((failed + 1))
+        failed_files+=("lib_embedding")
+    fi
+    rm -rf "$embdir"
+fi
+
+# --- --profile check -------------------------------------------------------
+# `--profile` instruments every function and prints a call-count/timing table
+# to stderr at exit. fib(10) makes 177 calls (recursion is not tail-call
+# rewritten under --profile) and the method is called 5 times.
+total=$((total + 1))
+echo "==> --profile call counts"
+profdir=$(mktemp -d)
+cat > "$profdir/prof.tr" <<'TREOF'
+def fib(n: int) -> int:
+    if n < 2: return n
+    return fib(n - 1) + fib(n - 2)
+
+class Counter:
+    pub n: int
+    pub def bump(self):
+        self.n = self.n + 1
+
+def main():
+    mut c = Counter()
+    mut i = 0
+    while i < 5:
+        c.bump()
+        i = i + 1
+    print(fib(10) + c.n)
+TREOF
+pout=$("$TAURAROC" --run --profile "$profdir/prof.tr" 2>&1)
+if ! echo "$pout" | grep -qE '^60$' \
+   || ! echo "$pout" | grep -qE '^ +177 .* fib$' \
+   || ! echo "$pout" | grep -qE '^ +5 .* Counter\.bump$'; then
+    echo "$pout"
+    echo "  FAILED"
+    failed=$((failed + 1))
+    failed_files+=("profile_counts")
+fi
+rm -rf "$profdir"
+
+# --- int overflow trap -----------------------------------------------------
+# factorial(25) does not fit in a 64-bit int: the multiplication must trap with
+# an OverflowError, not print the wrapped value (7034535277573963776).
+total=$((total + 1))
+echo "==> int overflow trap"
+ovdir=$(mktemp -d)
+cat > "$ovdir/ov.tr" <<'TREOF'
+def fact(n: int) -> int:
+    if n <= 1: return 1
+    return n * fact(n - 1)
+
+def main():
+    print(fact(25))
+TREOF
+oout=$("$TAURAROC" --run "$ovdir/ov.tr" 2>&1)
+if ! echo "$oout" | grep -q 'OverflowError: integer overflow in 21 \* 2432902008176640000' \
+   || echo "$oout" | grep -q '7034535277573963776'; then
+    echo "$oout"
+    echo "  FAILED"
+    failed=$((failed + 1))
+    failed_files+=("int_overflow_trap")
+fi
+rm -rf "$ovdir"
+
+# --- native reductions over typed lists -------------------------------------
+# sum/min/max/sorted of a List[int] compile to loops over the long long
+# elements (an int sum adds with the overflow-checked _tr_int_add) and the
+# typed sort, not a per-element generic call. Printing sorted(xs) copies the
+# list once.
+total=$((total + 1))
+echo "==> native list reductions"
+nrdir=$(mktemp -d)
+cat > "$nrdir/nr.tr" <<'TREOF'
+def main():
+    mut xs: List[int] = [5, 3, 9, 1]
+    mut total = sum(xs)
+    print(total, min(xs), max(xs))
+    print(sorted(xs))
+TREOF
+nrout=$("$TAURAROC" --run "$nrdir/nr.tr" 2>&1)
+rm -rf build
+"$TAURAROC" "$nrdir/nr.tr" --emit c >/dev/null 2>&1
+nrc="build/main.c"
+if [ "$nrout" != $'18 1 9\n[1, 3, 5, 9]' ] || [ ! -f "$nrc" ] \
+   || ! grep -q 'long long _t[0-9]* = (long long)(0);' "$nrc" \
+   || ! grep -q '= _tr_int_add(_t[0-9]*, (long long)_t[0-9]*_v);' "$nrc" \
+   || [ "$(grep -o '_tr_list_sort_i64' "$nrc" | wc -l)" -ne 1 ] \
+   || [ "$(grep -o '_tr_list_clone_i64' "$nrc" | wc -l)" -ne 1 ]; then
+    echo "$nrout"
+    echo "  FAILED"
+    failed=$((failed + 1))
+    failed_files+=("native_list_reductions")
+fi
+rm -rf "$nrdir" build
+
+# --- exception chain rendering ---------------------------------------------
+# An unhandled `raise X from Y` prints Y, Python's "direct cause" line, then X;
+# a raise inside an except body prints the handled exception as its context.
+# The handler left by `break` must not turn up as a context afterwards.
+total=$((total + 1))
+echo "==> exception chain rendering"
+exdir=$(mktemp -d)
+cat > "$exdir/chain.tr" <<'TREOF'
+def main():
+    while true:
+        try:
+            raise("stale")
+        except e:
+            break
+    try:
+        try:
+            raise("KeyError: 'port'")
+        except e:
+            raise "ConfigError: missing port" from e
+    except e:
+        raise("StartupError: cannot start")
+TREOF
+xout=$("$TAURAROC" --run "$exdir/chain.tr" 2>&1 | grep -v '^Aborted')
+xwant="KeyError: 'port'
+
+The above exception was the direct cause of the following exception:
+
+ConfigError: missing port
+
+During handling of the above exception, another exception occurred:
+
+Unhandled exception: StartupError: cannot start"
+if [ "$xout" != "$xwant" ]; then
+    echo "$xout"
+    echo "  FAILED"
+    failed=$((failed + 1))
+    failed_files+=("exception_chain_render")
+fi
+rm -rf "$exdir"
+
+# --- compiled artifact round trip / unknown backend -------------------------
+# The executable from `-o` prints what `--run` prints, and an unknown backend
+# (there is no bytecode backend) is an error instead of a silent C build.
+total=$((total + 1))
+echo "==> -o artifact round trip"
+rtdir=$(mktemp -d)
+cat > "$rtdir/rt.tr" <<'TREOF'
+def main():
+    mut xs = [3, 1, 2]
+    print(sorted(xs))
+    print("done")
+TREOF
+rt_direct=$("$TAURAROC" --run "$rtdir/rt.tr" 2>&1)
+"$TAURAROC" "$rtdir/rt.tr" -o "$rtdir/rt" >/dev/null 2>&1
+rt_built=$("$rtdir/rt" 2>&1)
+rt_bc=$("$TAURAROC" "$rtdir/rt.tr" --backend bytecode -o "$rtdir/rt.trc" 2>&1)
+rt_bc_status=$?
+if [ "$rt_direct" != "$rt_built" ] || [ -z "$rt_built" ] \
+   || [ $rt_bc_status -eq 0 ] || ! echo "$rt_bc" | grep -q "unknown backend 'bytecode'"; then
+    echo "  FAILED (direct: '$rt_direct', built: '$rt_built', bytecode: '$rt_bc')"
+    failed=$((failed + 1))
+    failed_files+=("artifact_round_trip")
+fi
+rm -rf "$rtdir"
+
+# --- demand-driven std emission --------------------------------------------
+# Only std modules reachable from the program's imports are compiled: a
+# program with no imports is a single main.c, and importing std.encoding.json
+# brings in json.c and its own dependencies but nothing unrelated.
+total=$((total + 1))
+echo "==> demand-driven std emission"
+TAURAROC_ABS="$(cd "$(dirname "$TAURAROC")" && pwd)/$(basename "$TAURAROC")"
+dddir=$(mktemp -d)
+printf 'def main():\n    print("hi")\n' > "$dddir/main.tr"
+( cd "$dddir" && "$TAURAROC_ABS" main.tr --emit c >/dev/null 2>&1 )
+dd_bare=$(cd "$dddir" && find build -name '*.c' | sort | tr '\n' ' ')
+rm -rf "$dddir/build"
+printf 'from std.encoding.json import Json\n\ndef main():\n    print(Json.dumps(Json.loads("[1]")))\n' > "$dddir/main.tr"
+( cd "$dddir" && "$TAURAROC_ABS" main.tr --emit c >/dev/null 2>&1 )
+dd_json=$(cd "$dddir" && find build -name '*.c' | sort | tr '\n' ' ')
+if [ "$dd_bare" != "build/main.c " ] \
+   || ! echo "$dd_json" | grep -q 'std/encoding/json.c' \
+   || echo "$dd_json" | grep -qE 'std/(net|compress|crypto|math)/|toml\.c'; then
+    echo "  FAILED (bare: '$dd_bare', json: '$dd_json')"
+    failed=$((failed + 1))
+    failed_files+=("demand_driven_std")
+fi
+rm -rf "$dddir"
+
+# --- constant folding --------------------------------------------------------
+# At the default -O2 literal arithmetic reaches the C as one literal; -O0 keeps
+# the ops, and an overflowing op is never folded so it still raises at run time.
+total=$((total + 1))
+echo "==> constant folding"
+cfdir=$(mktemp -d)
+printf 'def main():\n    mut day = 60 * 60 * 24\n    mut big = 9223372036854775807 + 1\n    print(day, big)\n' > "$cfdir/main.tr"
+( cd "$cfdir" && "$TAURAROC_ABS" main.tr --emit c >/dev/null 2>&1 )
+cf_o2=$(cat "$cfdir/build/main.c" 2>/dev/null)
+rm -rf "$cfdir/build"
+( cd "$cfdir" && "$TAURAROC_ABS" -O0 main.tr --emit c >/dev/null 2>&1 )
+cf_o0=$(cat "$cfdir/build/main.c" 2>/dev/null)
+if ! echo "$cf_o2" | grep -q 'day = 86400LL;' \
+   || ! echo "$cf_o2" | grep -q '_tr_int_add(9223372036854775807LL, 1LL)' \
+   || ! echo "$cf_o0" | grep -q '_tr_int_mul(_tr_int_mul(60LL, 60LL), 24LL)'; then
+    echo "  FAILED"
+    failed=$((failed + 1))
+    failed_files+=("const_fold")
+fi
+rm -rf "$cfdir"
+
+# --- @inline functions ------------------------------------------------------
+# An @inline function is emitted `static inline`: checked arithmetic and the
+# zero-divisor check of `%` call the runtime's static helpers, which a
+# non-static inline definition may not use.
+total=$((total + 1))
+echo "==> @inline functions"
+ildir=$(mktemp -d)
+printf '@inline\ndef square(x: int) -> int:\n    return x * x\n\n@inline\ndef is_even(n: int) -> bool:\n    return n %% 2 == 0\n\ndef main():\n    print(square(7), is_even(7))\n' > "$ildir/main.tr"
+il_build=$(cd "$ildir" && "$TAURAROC_ABS" main.tr -o il 2>&1)
+il_out=$("$ildir/il" 2>&1)
+if [ "$il_out" != "49 false" ] || echo "$il_build" | grep -q 'warning:'; then
+    echo "  FAILED (output: '$il_out')"
+    echo "$il_build" | grep 'warning:' | head -3
+    failed=$((failed + 1))
+    failed_files+=("inline_static")
+fi
+rm -rf "$ildir"
+
+# --- print() through the vtable ---------------------------------------------
+# print(obj) on a base-typed reference runs the subclass's __str__ override,
+# like str(obj) and f"{obj}" (asserted in tests/regression/virtual_dispatch.tr).
+total=$((total + 1))
+echo "==> print virtual __str__"
+vpdir=$(mktemp -d)
+printf 'class A:\n    pub n: int\n\nextend A:\n    pub def __str__(self) -> str:\n        return "A"\n\nclass B extends A:\n    pub m: int\n\nextend B:\n    pub def __str__(self) -> str:\n        return "B"\n\ndef main():\n    b = B()\n    a: A = b\n    print(a)\n    print(A())\n' > "$vpdir/main.tr"
+(cd "$vpdir" && "$TAURAROC_ABS" main.tr -o vp >/dev/null 2>&1)
+vp_out=$("$vpdir/vp" 2>&1)
+if [ "$vp_out" != "$(printf 'B\nA')" ]; then
+    echo "  FAILED (output: '$vp_out')"
+    failed=$((failed + 1))
+    failed_files+=("print_virtual_str")
+fi
+rm -rf "$vpdir"
+
+# --- --emit lir disassembly -------------------------------------------------
+# Each LIR instruction prints on its own numbered line with resolved operands:
+# string literals as their repr, globals by name, and branch targets as the
+# offset of the target block's first instruction.
+total=$((total + 1))
+echo "==> --emit lir"
+lirdir=$(mktemp -d)
+cat > "$lirdir/l.tr" <<'TREOF'
+mut hits: int = 0
+
+def pick(n: int) -> int:
+    if n < 2:
+        return n
+    return 7
+
+def main():
+    hits = hits + pick(3)
+    print("hits")
+TREOF
+lout=$("$TAURAROC" -O0 --emit lir "$lirdir/l.tr" 2>&1)
+if ! echo "$lout" | grep -q '^Disassembly of pick(n):$' \
+   || ! echo "$lout" | grep -qE '^ +3 COND_BR +%2, 4 \(bb1\), 6 \(bb2\)$' \
+   || ! echo "$lout" | grep -qE "LOAD_STR +%[0-9]+, 0 \('hits'\)$" \
+   || ! echo "$lout" | grep -qE 'LOAD_GLOBAL +%[0-9]+, 0 \(hits\)$'; then
+    echo "$lout"
+    echo "  FAILED"
+    failed=$((failed + 1))
+    failed_files+=("emit_lir")
+fi
+rm -rf "$lirdir"
+
+# --- freestanding --target-arch boot code ----------------------------------
+# `--freestanding --target-arch <a>` must emit that architecture's _start (with
+# its own asm dialect) and a main.c / types header with no libc #include.
+total=$((total + 1))
+echo "==> freestanding --target-arch"
+TAURAROC_ABS="$(cd "$(dirname "$TAURAROC")" && pwd)/$(basename "$TAURAROC")"
+fsdir=$(mktemp -d)
+cat > "$fsdir/k.tr" <<'TREOF'
+mut ticks: int = 5
+
+@entry
+def kmain():
+    ticks = ticks + 1
+TREOF
+fsfail=""
+for fsarch in "riscv64:la sp, _stack_top" "aarch64:add x0, x0, :lo12:_stack_top" "x86_64:lea _stack_top(%rip), %rsp"; do
+    arch="${fsarch%%:*}"
+    want="${fsarch#*:}"
+    rm -rf "$fsdir/build"
+    ( cd "$fsdir" && "$TAURAROC_ABS" k.tr --freestanding --target-arch "$arch" --emit c >/dev/null 2>&1 )
+    if ! grep -q 'void _start(void)' "$fsdir/build/main.c" 2>/dev/null \
+       || ! grep -qF "$want" "$fsdir/build/main.c" \
+       || grep -q '#include <' "$fsdir/build/main.c" "$fsdir/build/tauraro_types.h"; then
+        fsfail="$fsfail $arch"
+    fi
+done
+if [ -n "$fsfail" ]; then
+    echo "  FAILED:$fsfail"
+    failed=$((failed + 1))
+    failed_files+=("freestanding_target_arch")
+fi
+rm -rf "$fsdir"
+
+# --- check subcommand ------------------------------------------------------
+# `tauraroc check <file>` runs the analysis passes only: every semantic error
+# is reported and the exit status is nonzero, and a clean file passes
+# without writing a build/ directory or an executable.
+total=$((total + 1))
+echo "==> check subcommand"
+ckdir=$(mktemp -d)
+printf 'def main():\n    print(first_missing)\n    print(second_missing)\n' > "$ckdir/bad.tr"
+printf 'def main():\n    print(1)\n' > "$ckdir/ok.tr"
+ck_bad=$(cd "$ckdir" && "$TAURAROC_ABS" check bad.tr 2>&1)
+ck_bad_status=$?
+ck_ok=$(cd "$ckdir" && "$TAURAROC_ABS" check --strict ok.tr 2>&1)
+ck_ok_status=$?
+if [ $ck_bad_status -eq 0 ] \
+   || [ "$(echo "$ck_bad" | grep -c "\[N-3\] name '[a-z_]*_missing' is not defined")" -ne 2 ] \
+   || [ $ck_ok_status -ne 0 ] || [ "$ck_ok" != "Check passed: no errors found." ] \
+   || [ -e "$ckdir/build" ] || [ -e "$ckdir/ok" ]; then
+    echo "$ck_bad"
+    echo "$ck_ok"
+    echo "  FAILED"
+    failed=$((failed + 1))
+    failed_files+=("check_subcommand")
+fi
+rm -rf "$ckdir"
+
 echo ""
 echo "==================================="
 echo "Test files: $total, failed: $failed"